@@ -119,7 +119,7 @@ fn main() -> anyhow::Result<()> {
 
             let mut count: u64 = 0;
 
-            while let Some(_) = splitter.split_record()? {
+            while splitter.split_record()?.is_some() {
                 count += 1;
             }
 
@@ -0,0 +1,52 @@
+use std::io::Result;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::buffer::{Buffered, Positioned, Scratch};
+
+impl<R: AsyncRead + Unpin> Buffered for BufReader<R> {
+    #[inline(always)]
+    fn buffer(&self) -> &[u8] {
+        BufReader::buffer(self)
+    }
+
+    #[inline(always)]
+    fn consume(&mut self, amt: usize) {
+        AsyncBufReadExt::consume(self, amt);
+    }
+}
+
+/// Async counterpart of [`BufReaderWithPosition`](crate::buffer::BufReaderWithPosition).
+pub type AsyncBufReaderWithPosition<R> = Positioned<BufReader<R>>;
+
+impl<R: AsyncRead + Unpin> AsyncBufReaderWithPosition<R> {
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self::wrap(BufReader::with_capacity(capacity, reader))
+    }
+
+    #[inline(always)]
+    pub async fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.has_pending() {
+            return Ok(self.buffer());
+        }
+
+        self.inner_mut().fill_buf().await
+    }
+}
+
+/// Async counterpart of [`ScratchBuffer`](crate::buffer::ScratchBuffer).
+pub type AsyncScratchBuffer<R> = Scratch<BufReader<R>>;
+
+impl<R: AsyncRead + Unpin> AsyncScratchBuffer<R> {
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self::wrap(
+            AsyncBufReaderWithPosition::with_capacity(capacity, reader),
+            capacity,
+        )
+    }
+
+    #[inline(always)]
+    pub async fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner_mut().fill_buf().await
+    }
+}
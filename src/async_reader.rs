@@ -0,0 +1,336 @@
+use tokio::io::AsyncRead;
+
+use crate::async_buffer::AsyncBufReaderWithPosition;
+use crate::core::CoreReader;
+use crate::dialect::Trim;
+use crate::error;
+use crate::preamble::SkipLines;
+use crate::reader::ReaderBuilder;
+use crate::records::{ByteRecord, ByteRecordBuilder};
+use crate::tracker::{Step, Tracker};
+use crate::utils::{count_lines, trim_bom};
+
+#[cfg(feature = "str")]
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "str")]
+use crate::records::StringRecord;
+
+impl ReaderBuilder {
+    /// Create a new [`AsyncReader`] using the provided reader implementing
+    /// [`tokio::io::AsyncRead`].
+    pub fn from_async_reader<R: AsyncRead + Unpin>(&self, reader: R) -> AsyncReader<R> {
+        AsyncReader {
+            buffer: AsyncBufReaderWithPosition::with_capacity(self.buffer_capacity, reader),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
+            flexible: self.flexible,
+            headers: ByteRecord::new(),
+            has_read: false,
+            must_reemit_headers: !self.has_headers,
+            has_headers: self.has_headers,
+            trim: self.trim,
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
+        }
+    }
+}
+
+/// An already configured copying/unescaping CSV reader working on a
+/// [`tokio::io::AsyncRead`] stream.
+///
+/// It behaves exactly like a [`Reader`](crate::Reader), except that buffers
+/// are filled asynchronously.
+///
+/// # Configuration
+///
+/// To configure an [`AsyncReader`], use [`ReaderBuilder::from_async_reader`].
+pub struct AsyncReader<R> {
+    buffer: AsyncBufReaderWithPosition<R>,
+    inner: CoreReader,
+    tracker: Tracker,
+    flexible: bool,
+    headers: ByteRecord,
+    has_read: bool,
+    must_reemit_headers: bool,
+    has_headers: bool,
+    trim: Trim,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Create a new async reader with default configuration using the provided
+    /// reader implementing [`tokio::io::AsyncRead`].
+    pub fn from_reader(reader: R) -> Self {
+        ReaderBuilder::new().from_async_reader(reader)
+    }
+
+    async fn read_byte_record_impl(&mut self, record: &mut ByteRecord) -> error::Result<bool> {
+        record.clear();
+
+        let mut record_builder = ByteRecordBuilder::wrap(record);
+
+        self.tracker.start(self.buffer.position());
+
+        let expected_len = (!self.flexible && self.has_read).then(|| self.headers.len());
+
+        loop {
+            let input = self.buffer.fill_buf().await?;
+            let read = self.inner.read_record(input, &mut record_builder);
+
+            match self.tracker.read_step(
+                &mut self.buffer,
+                read,
+                &mut self.inner,
+                &mut record_builder,
+                expected_len,
+            )? {
+                Step::End => return Ok(false),
+                Step::Continue => continue,
+                Step::Record => return Ok(true),
+            }
+        }
+    }
+
+    #[inline]
+    async fn on_first_read(&mut self) -> error::Result<()> {
+        if self.has_read {
+            return Ok(());
+        }
+
+        // Trimming BOM
+        let input = self.buffer.fill_buf().await?;
+        let bom_len = trim_bom(input);
+        self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        self.preamble = self
+            .skip_lines
+            .skip_async(&mut self.buffer, &self.inner)
+            .await?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
+
+        // Reading headers
+        let mut headers = ByteRecord::new();
+
+        self.inner.trim = if self.has_headers {
+            self.trim.headers()
+        } else {
+            self.trim.fields()
+        };
+
+        let has_data = self.read_byte_record_impl(&mut headers).await?;
+
+        self.inner.trim = self.trim.fields();
+
+        if !has_data {
+            self.must_reemit_headers = false;
+        }
+
+        self.headers = headers;
+        self.has_read = true;
+
+        Ok(())
+    }
+
+    /// Returns whether this reader has been configured to interpret the first
+    /// record as a header.
+    #[inline]
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
+    /// Attempt to return a reference to this reader's first record.
+    #[inline]
+    pub async fn byte_headers(&mut self) -> error::Result<&ByteRecord> {
+        self.on_first_read().await?;
+
+        Ok(&self.headers)
+    }
+
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    #[inline]
+    pub async fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read().await?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub async fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read().await?;
+
+        Ok(self.had_bom)
+    }
+
+    /// Attempt to read the next CSV record into a pre-allocated [`ByteRecord`].
+    ///
+    /// Returns a boolean indicating whether a record was actually read or if we
+    /// reached the end of the stream.
+    #[inline(always)]
+    pub async fn read_byte_record(&mut self, record: &mut ByteRecord) -> error::Result<bool> {
+        self.on_first_read().await?;
+
+        if self.must_reemit_headers {
+            self.headers.clone_into(record);
+            self.must_reemit_headers = false;
+            return Ok(true);
+        }
+
+        self.read_byte_record_impl(record).await
+    }
+
+    #[cfg(feature = "str")]
+    pub async fn read_record(&mut self, record: &mut StringRecord) -> error::Result<bool> {
+        if self.read_byte_record(record.as_inner_mut()).await? {
+            if !record.validate_utf8() {
+                Err(Error::new(ErrorKind::Utf8Error))
+            } else {
+                Ok(true)
+            }
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Unwrap into the underlying reader.
+    ///
+    /// **BEWARE**: any already buffered data will be lost!
+    pub fn into_inner(self) -> R {
+        self.buffer.into_inner().into_inner()
+    }
+
+    /// Returns the current byte offset of the reader in the wrapped stream.
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
+        if self.must_reemit_headers {
+            self.tracker.record_position().byte
+        } else {
+            self.buffer.position()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_all<R: AsyncRead + Unpin>(
+        reader: &mut AsyncReader<R>,
+    ) -> error::Result<Vec<ByteRecord>> {
+        let mut records = Vec::new();
+        let mut record = ByteRecord::new();
+
+        while reader.read_byte_record(&mut record).await? {
+            records.push(record.clone());
+        }
+
+        Ok(records)
+    }
+
+    #[tokio::test]
+    async fn test_read_byte_record() -> error::Result<()> {
+        let csv = "name,surname,age\n\"john\",\"landy, the \"\"everlasting\"\" bastard\",45\nlucy,rose,\"67\"\njermaine,jackson,\"89\"\n\nkarine,loucan,\"52\"\nrose,\"glib\",12\n\"guillaume\",\"plique\",\"42\"\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut sync_reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_reader(csv.as_bytes());
+            let expected = sync_reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_async_reader(csv.as_bytes());
+
+            assert_eq!(read_all(&mut reader).await?, expected);
+            assert_eq!(reader.position(), csv.len() as u64);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_headers() -> error::Result<()> {
+        let data = "\u{FEFF}sep=,\nname,surname\njohn,landis\nlucy,rose,extra\n";
+
+        for capacity in [32usize, 4, 3] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .from_async_reader(data.as_bytes());
+
+            assert!(reader.input_had_bom().await?);
+            assert_eq!(reader.preamble().await?, b"sep=,\n");
+            assert_eq!(reader.byte_headers().await?, &brec!["name", "surname"]);
+
+            let mut record = ByteRecord::new();
+
+            assert!(reader.read_byte_record(&mut record).await?);
+            assert_eq!(record, brec!["john", "landis"]);
+
+            let err = reader.read_byte_record(&mut record).await.unwrap_err();
+            let mut sync_reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .from_reader(data.as_bytes());
+
+            assert_eq!(
+                err.to_string(),
+                sync_reader
+                    .byte_records()
+                    .find_map(|result| result.err())
+                    .unwrap()
+                    .to_string()
+            );
+        }
+
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_async_reader("a,b\nc\n".as_bytes());
+
+        assert_eq!(
+            read_all(&mut reader).await?,
+            vec![brec!["a", "b"], brec!["c"]]
+        );
+
+        let mut reader = AsyncReader::from_reader("".as_bytes());
+        assert!(reader.byte_headers().await?.is_empty());
+        assert!(read_all(&mut reader).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spawn() -> error::Result<()> {
+        // NOTE: futures must be `Send` to be spawned
+        let handle = tokio::spawn(async move {
+            let mut reader = ReaderBuilder::new()
+                .skip_lines_while(|line| line.starts_with(b"#"))
+                .from_async_reader(&b"# metadata\nname\njohn\nlucy\n"[..]);
+
+            read_all(&mut reader).await
+        });
+
+        assert_eq!(handle.await.unwrap()?, vec![brec!["john"], brec!["lucy"]]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use tokio::io::AsyncRead;
+
+use crate::async_buffer::AsyncScratchBuffer;
+use crate::core::CoreReader;
+use crate::error;
+use crate::preamble::SkipLines;
+use crate::splitter::SplitterBuilder;
+use crate::tracker::{Step, Tracker};
+use crate::utils::{count_lines, trim_bom};
+
+impl SplitterBuilder {
+    /// Create a new [`AsyncSplitter`] using the provided reader implementing
+    /// [`tokio::io::AsyncRead`].
+    pub fn from_async_reader<R: AsyncRead + Unpin>(&self, reader: R) -> AsyncSplitter<R> {
+        AsyncSplitter {
+            buffer: AsyncScratchBuffer::with_capacity(self.buffer_capacity.unwrap_or(8192), reader),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
+            headers: Vec::new(),
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
+            has_read: false,
+            has_headers: self.has_headers,
+            must_reemit_headers: !self.has_headers,
+        }
+    }
+}
+
+/// An already configured CSV record splitter working on a
+/// [`tokio::io::AsyncRead`] stream.
+///
+/// It behaves exactly like a [`Splitter`](crate::Splitter), except that
+/// buffers are filled asynchronously.
+///
+/// # Configuration
+///
+/// To configure an [`AsyncSplitter`], use [`SplitterBuilder::from_async_reader`].
+#[derive(Debug)]
+pub struct AsyncSplitter<R> {
+    buffer: AsyncScratchBuffer<R>,
+    inner: CoreReader,
+    tracker: Tracker,
+    headers: Vec<u8>,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
+    has_read: bool,
+    has_headers: bool,
+    must_reemit_headers: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncSplitter<R> {
+    /// Create a new async splitter with default configuration using the
+    /// provided reader implementing [`tokio::io::AsyncRead`].
+    pub fn from_reader(reader: R) -> Self {
+        SplitterBuilder::new().from_async_reader(reader)
+    }
+
+    /// Returns whether this reader has been configured to interpret the first
+    /// record as a header.
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
+    /// Attempt to return a reference to this splitter's first record.
+    pub async fn byte_headers(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read().await?;
+
+        Ok(&self.headers)
+    }
+
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    pub async fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read().await?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub async fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read().await?;
+
+        Ok(self.had_bom)
+    }
+
+    #[inline(always)]
+    async fn on_first_read(&mut self) -> error::Result<()> {
+        if self.has_read {
+            return Ok(());
+        }
+
+        let input = self.buffer.fill_buf().await?;
+        let bom_len = trim_bom(input);
+        self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        self.preamble = self
+            .skip_lines
+            .skip_async(self.buffer.inner_mut(), &self.inner)
+            .await?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
+
+        if let Some(record) = self.split_record_impl().await? {
+            self.headers = record.to_vec();
+        } else {
+            self.must_reemit_headers = false;
+        }
+
+        self.has_read = true;
+
+        Ok(())
+    }
+
+    /// Consume the reader completely to count the number of records as fast as
+    /// possible.
+    pub async fn count_records(&mut self) -> error::Result<u64> {
+        self.on_first_read().await?;
+        self.buffer.reset();
+
+        let mut count: u64 = 0;
+
+        if self.must_reemit_headers {
+            count += 1;
+            self.must_reemit_headers = false;
+        }
+
+        self.tracker.start(self.buffer.position());
+
+        loop {
+            let input = self.buffer.fill_buf().await?;
+            let read = self.inner.split_record(input);
+
+            match self.tracker.count_step(self.buffer.inner_mut(), read)? {
+                Step::End => break,
+                Step::Continue => continue,
+                Step::Record => count += 1,
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn split_record_impl(&mut self) -> error::Result<Option<&[u8]>> {
+        self.buffer.reset();
+
+        self.tracker.start(self.buffer.position());
+
+        loop {
+            let input = self.buffer.fill_buf().await?;
+            let read = self.inner.split_record(input);
+
+            match self
+                .tracker
+                .split_step(&mut self.buffer, read, &mut self.inner)?
+            {
+                Step::End => return Ok(None),
+                Step::Continue => continue,
+                Step::Record => return Ok(Some(self.inner.trim_terminator(self.buffer.flushed()))),
+            }
+        }
+    }
+
+    /// Attempt to split the next CSV record and return an optional reference to
+    /// its byte slice.
+    ///
+    /// Returns `Ok(None)` when the reader is fully consumed.
+    pub async fn split_record(&mut self) -> error::Result<Option<&[u8]>> {
+        self.on_first_read().await?;
+
+        if self.must_reemit_headers {
+            self.must_reemit_headers = false;
+            return Ok(Some(&self.headers));
+        }
+
+        self.split_record_impl().await
+    }
+
+    /// Attempt to split the next CSV record and return an optional byte offset
+    /// as well as a reference to its byte slice.
+    ///
+    /// Returns `Ok(None)` when the reader is fully consumed.
+    pub async fn split_record_with_position(&mut self) -> error::Result<Option<(u64, &[u8])>> {
+        self.on_first_read().await?;
+
+        let pos = self.position();
+
+        if self.must_reemit_headers {
+            self.must_reemit_headers = false;
+            return Ok(Some((pos, &self.headers)));
+        }
+
+        match self.split_record_impl().await {
+            Ok(Some(record)) => Ok(Some((pos, record))),
+            Ok(None) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the current byte offset of the reader in the wrapped stream.
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
+        if self.must_reemit_headers {
+            self.tracker.record_position().byte
+        } else {
+            self.buffer.position()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_split_record() -> error::Result<()> {
+        let csv = "name,surname\n\"john\",\"landy, the \"\"everlasting\"\" bastard\"\nlucy,rose\n\njermaine,jackson\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut sync_splitter =
+                SplitterBuilder::with_capacity(capacity).from_reader(csv.as_bytes());
+
+            let mut expected = Vec::new();
+
+            while let Some((pos, record)) = sync_splitter.split_record_with_position()? {
+                expected.push((pos, record.to_vec()));
+            }
+
+            let mut splitter =
+                SplitterBuilder::with_capacity(capacity).from_async_reader(csv.as_bytes());
+
+            assert_eq!(splitter.byte_headers().await?, b"name,surname");
+
+            let mut records = Vec::new();
+
+            while let Some((pos, record)) = splitter.split_record_with_position().await? {
+                records.push((pos, record.to_vec()));
+            }
+
+            assert_eq!(records, expected);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_async_reader(csv.as_bytes());
+
+            assert_eq!(splitter.count_records().await?, 4);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+use std::io;
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::error;
+use crate::records::{ByteRecord, ZeroCopyByteRecord};
+use crate::writer::{RecordEncoder, WriterBuilder};
+
+impl WriterBuilder {
+    /// Create a new [`AsyncWriter`] using the provided writer implementing
+    /// [`tokio::io::AsyncWrite`].
+    pub fn from_async_writer<W: AsyncWrite + Unpin>(&self, writer: W) -> AsyncWriter<W> {
+        AsyncWriter {
+            encoder: self.encoder(),
+            buffer: Vec::with_capacity(self.buffer_capacity),
+            capacity: self.buffer_capacity,
+            inner: writer,
+        }
+    }
+}
+
+/// An already configured CSV writer working on a [`tokio::io::AsyncWrite`]
+/// stream.
+///
+/// It behaves exactly like a [`Writer`](crate::Writer), except that records
+/// are formatted into an inner buffer which is written asynchronously each time
+/// it exceeds the configured capacity.
+///
+/// **BEWARE**: buffered data cannot be written when the writer is dropped, so
+/// remember to call [`Self::flush`] or [`Self::into_inner`] when you are done!
+///
+/// # Configuration
+///
+/// To configure an [`AsyncWriter`], use [`WriterBuilder::from_async_writer`].
+pub struct AsyncWriter<W> {
+    encoder: RecordEncoder,
+    buffer: Vec<u8>,
+    capacity: usize,
+    inner: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    /// Create a new async writer with default configuration using the provided
+    /// writer implementing [`tokio::io::AsyncWrite`].
+    pub fn from_writer(writer: W) -> Self {
+        WriterBuilder::new().from_async_writer(writer)
+    }
+
+    #[inline]
+    async fn write_buffer_if_full(&mut self) -> io::Result<()> {
+        if self.buffer.len() >= self.capacity {
+            self.write_buffer().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_buffer(&mut self) -> io::Result<()> {
+        self.inner.write_all(&self.buffer).await?;
+        self.buffer.clear();
+
+        Ok(())
+    }
+
+    /// Write any buffered data and flush the underlying writer.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer().await?;
+        self.inner.flush().await
+    }
+
+    /// Write the given "record" while foregoing any quoting/escaping.
+    ///
+    /// This method accepts any item implementing [`IntoIterator`] and yielding
+    /// references to byte slices.
+    ///
+    /// **BEWARE**: if written data needed escaping, invalid CSV will be
+    /// written!
+    ///
+    /// Only use this method when you can guarantee you are doing the right
+    /// thing and want the extra performance.
+    pub async fn write_record_no_quoting<I, T>(&mut self, record: I) -> error::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.encoder
+            .write_record_no_quoting(&mut self.buffer, record)?;
+        self.write_buffer_if_full().await?;
+
+        Ok(())
+    }
+
+    /// Write the given [`ByteRecord`] while foregoing any quoting/escaping.
+    ///
+    /// **BEWARE**: if written data needed escaping, invalid CSV will be
+    /// written!
+    ///
+    /// Only use this method when you can guarantee you are doing the right
+    /// thing and want the extra performance.
+    #[inline(always)]
+    pub async fn write_byte_record_no_quoting(&mut self, record: &ByteRecord) -> error::Result<()> {
+        self.write_record_no_quoting(record.iter()).await
+    }
+
+    /// Write the given "record".
+    ///
+    /// This method accepts any item implementing [`IntoIterator`] and yielding
+    /// references to byte slices.
+    pub async fn write_record<I, T>(&mut self, record: I) -> error::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.encoder.write_record(&mut self.buffer, record)?;
+        self.write_buffer_if_full().await?;
+
+        Ok(())
+    }
+
+    /// Write the given [`ByteRecord`].
+    #[inline(always)]
+    pub async fn write_byte_record(&mut self, record: &ByteRecord) -> error::Result<()> {
+        self.write_record(record.iter()).await
+    }
+
+    /// Write the given [`ZeroCopyByteRecord`] using a fast path if reader & writer
+    /// have matching delimiter, terminator & quoting, and the reader does not
+    /// trim fields.
+    pub async fn write_zero_copy_byte_record(
+        &mut self,
+        record: &ZeroCopyByteRecord<'_>,
+    ) -> error::Result<()> {
+        self.encoder
+            .write_zero_copy_byte_record(&mut self.buffer, record)?;
+        self.write_buffer_if_full().await?;
+
+        Ok(())
+    }
+
+    /// Same as [`Self::write_zero_copy_byte_record`], but only write the
+    /// given selection of cell indices.
+    pub async fn write_zero_copy_byte_record_indices(
+        &mut self,
+        record: &ZeroCopyByteRecord<'_>,
+        indices: &[usize],
+    ) -> error::Result<()> {
+        self.encoder
+            .write_zero_copy_byte_record_indices(&mut self.buffer, record, indices)?;
+        self.write_buffer_if_full().await?;
+
+        Ok(())
+    }
+
+    /// Write the given byte slice, as-is, without quoting/escaping, with an
+    /// added newline.
+    ///
+    /// **BEWARE**: if written data needed escaping, invalid CSV will be
+    /// written!
+    ///
+    /// This method can typically be used with slices yielded by
+    /// [`AsyncSplitter.split_record`](crate::AsyncSplitter::split_record).
+    pub async fn write_splitted_record(&mut self, record: &[u8]) -> error::Result<()> {
+        self.encoder
+            .write_splitted_record(&mut self.buffer, record)?;
+        self.write_buffer_if_full().await?;
+
+        Ok(())
+    }
+
+    /// Flush the writer and return the underlying writer.
+    pub async fn into_inner(mut self) -> io::Result<W> {
+        self.flush().await?;
+
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dialect::Terminator;
+    use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+    use crate::{AsyncSplitter, Writer};
+
+    #[tokio::test]
+    async fn test_write_record() -> error::Result<()> {
+        let records = [
+            brec!["name", "surname", "age"],
+            brec!["john", "landy, the \"everlasting\" bastard", "45"],
+            brec![""],
+            brec!["lucy", "rose\nmary", "67"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut builder = WriterBuilder::with_capacity(capacity);
+            builder
+                .delimiter(b';')
+                .escape(Some(b'\\'))
+                .terminator(Terminator::CRLF)
+                .flexible(true);
+
+            let mut sync_writer = builder.from_writer(Vec::new());
+            let mut writer = builder.from_async_writer(Vec::new());
+
+            for record in records.iter() {
+                sync_writer.write_byte_record(record)?;
+                writer.write_byte_record(record).await?;
+            }
+
+            assert_eq!(
+                writer.into_inner().await?,
+                sync_writer.into_inner().unwrap()
+            );
+        }
+
+        let mut writer = AsyncWriter::from_writer(Vec::new());
+        writer.write_record(["a", "b"]).await?;
+        assert!(writer.write_record(["c"]).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_zero_copy_byte_record() -> error::Result<()> {
+        let csv = "name,surname\n\"john\",\"landy, the \"\"everlasting\"\" bastard\"\nlucy,rose\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            for delimiter in [b',', b'\t'] {
+                let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                    .has_headers(false)
+                    .from_reader(csv.as_bytes());
+                let mut builder = WriterBuilder::with_capacity(capacity);
+                builder.delimiter(delimiter).flexible(true);
+
+                let mut sync_writer = builder.from_writer(Vec::new());
+                let mut writer = builder.from_async_writer(Vec::new());
+
+                while let Some(record) = reader.read_byte_record()? {
+                    sync_writer.write_zero_copy_byte_record(&record)?;
+                    sync_writer.write_zero_copy_byte_record_indices(&record, &[1])?;
+                    writer.write_zero_copy_byte_record(&record).await?;
+                    writer
+                        .write_zero_copy_byte_record_indices(&record, &[1])
+                        .await?;
+                }
+
+                assert_eq!(
+                    writer.into_inner().await?,
+                    sync_writer.into_inner().unwrap()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_splitted_record() -> error::Result<()> {
+        let csv = "name,surname\n\"john\",\"landy, the \"\"everlasting\"\" bastard\"\nlucy,rose\n";
+
+        let mut splitter = AsyncSplitter::from_reader(csv.as_bytes());
+        let mut writer = AsyncWriter::from_writer(Vec::new());
+
+        writer
+            .write_splitted_record(splitter.byte_headers().await?)
+            .await?;
+
+        while let Some(record) = splitter.split_record().await? {
+            writer.write_splitted_record(record).await?;
+        }
+
+        assert_eq!(writer.into_inner().await?, csv.as_bytes());
+
+        let mut sync_writer = Writer::from_writer(Vec::new());
+        sync_writer.write_splitted_record(b"test")?;
+        assert_eq!(sync_writer.into_inner().unwrap(), b"test\n");
+
+        Ok(())
+    }
+}
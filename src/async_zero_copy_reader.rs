@@ -0,0 +1,302 @@
+use tokio::io::AsyncRead;
+
+use crate::async_buffer::AsyncScratchBuffer;
+use crate::core::CoreReader;
+use crate::dialect::Trim;
+use crate::error;
+use crate::position::Position;
+use crate::preamble::SkipLines;
+use crate::records::{ByteRecord, ZeroCopyByteRecord};
+use crate::tracker::{Step, Tracker};
+use crate::utils::{count_lines, trim_bom};
+use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+impl ZeroCopyReaderBuilder {
+    /// Create a new [`AsyncZeroCopyReader`] using the provided reader
+    /// implementing [`tokio::io::AsyncRead`].
+    pub fn from_async_reader<R: AsyncRead + Unpin>(&self, reader: R) -> AsyncZeroCopyReader<R> {
+        AsyncZeroCopyReader {
+            buffer: AsyncScratchBuffer::with_capacity(self.buffer_capacity, reader),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
+            byte_headers: ByteRecord::new(),
+            raw_headers: (Vec::new(), Vec::new()),
+            headers_position: Position::new(),
+            seps: Vec::new(),
+            flexible: self.flexible,
+            has_read: false,
+            must_reemit_headers: !self.has_headers,
+            has_headers: self.has_headers,
+            trim: self.trim,
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
+        }
+    }
+}
+
+/// An already configured zero-copy CSV reader working on a
+/// [`tokio::io::AsyncRead`] stream.
+///
+/// It behaves exactly like a [`ZeroCopyReader`](crate::ZeroCopyReader), except
+/// that buffers are filled asynchronously.
+///
+/// # Configuration
+///
+/// To configure an [`AsyncZeroCopyReader`], use
+/// [`ZeroCopyReaderBuilder::from_async_reader`].
+pub struct AsyncZeroCopyReader<R> {
+    buffer: AsyncScratchBuffer<R>,
+    inner: CoreReader,
+    tracker: Tracker,
+    byte_headers: ByteRecord,
+    raw_headers: (Vec<usize>, Vec<u8>),
+    headers_position: Position,
+    seps: Vec<usize>,
+    flexible: bool,
+    has_read: bool,
+    must_reemit_headers: bool,
+    has_headers: bool,
+    trim: Trim,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncZeroCopyReader<R> {
+    /// Create a new async reader with default configuration using the provided
+    /// reader implementing [`tokio::io::AsyncRead`].
+    pub fn from_reader(reader: R) -> Self {
+        ZeroCopyReaderBuilder::new().from_async_reader(reader)
+    }
+
+    #[inline]
+    async fn on_first_read(&mut self) -> error::Result<()> {
+        if self.has_read {
+            return Ok(());
+        }
+
+        // Trimming BOM
+        let input = self.buffer.fill_buf().await?;
+        let bom_len = trim_bom(input);
+        self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        self.preamble = self
+            .skip_lines
+            .skip_async(self.buffer.inner_mut(), &self.inner)
+            .await?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
+
+        // Reading headers
+        let mut headers_seps = Vec::new();
+        let mut headers_slice = Vec::new();
+        let mut byte_headers = ByteRecord::new();
+        let mut headers_position = Position::new();
+
+        self.inner.trim = if self.has_headers {
+            self.trim.headers()
+        } else {
+            self.trim.fields()
+        };
+
+        if let Some(headers) = self.read_byte_record_impl().await? {
+            (headers_seps, headers_slice) = headers.to_parts();
+            headers_position = headers.position().copied().unwrap_or_default();
+            byte_headers = headers.to_byte_record();
+        } else {
+            self.must_reemit_headers = false;
+        }
+
+        self.inner.trim = self.trim.fields();
+
+        self.raw_headers = (headers_seps, headers_slice);
+        self.byte_headers = byte_headers;
+        self.headers_position = headers_position;
+
+        self.has_read = true;
+
+        Ok(())
+    }
+
+    /// Attempt to return a reference to this reader's first record.
+    #[inline]
+    pub async fn byte_headers(&mut self) -> error::Result<&ByteRecord> {
+        self.on_first_read().await?;
+
+        Ok(&self.byte_headers)
+    }
+
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    #[inline]
+    pub async fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read().await?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub async fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read().await?;
+
+        Ok(self.had_bom)
+    }
+
+    /// Returns whether this reader has been configured to interpret the first
+    /// record as a header.
+    #[inline]
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
+    async fn read_byte_record_impl(&mut self) -> error::Result<Option<ZeroCopyByteRecord<'_>>> {
+        self.buffer.reset();
+        self.seps.clear();
+
+        self.tracker.start(self.buffer.position());
+
+        let expected_len = (!self.flexible && self.has_read).then(|| self.raw_headers.0.len() + 1);
+
+        loop {
+            let seps_offset = self.buffer.saved().len();
+            let input = self.buffer.fill_buf().await?;
+
+            let read =
+                self.inner
+                    .split_record_and_find_separators(input, seps_offset, &mut self.seps);
+
+            match self.tracker.zero_copy_step(
+                &mut self.buffer,
+                read,
+                &mut self.inner,
+                &mut self.seps,
+                expected_len,
+            )? {
+                Step::End => return Ok(None),
+                Step::Continue => continue,
+                Step::Record => {
+                    let record = ZeroCopyByteRecord::new(
+                        self.inner.trim_terminator(self.buffer.flushed()),
+                        &self.seps,
+                        self.inner.delimiter,
+                        self.inner.terminator(),
+                        self.inner.quoting(),
+                        self.inner.trim,
+                    )
+                    .with_position(self.tracker.record_position());
+
+                    return Ok(Some(record));
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub async fn read_byte_record(&mut self) -> error::Result<Option<ZeroCopyByteRecord<'_>>> {
+        self.on_first_read().await?;
+
+        if self.must_reemit_headers {
+            self.must_reemit_headers = false;
+            return Ok(Some(
+                ZeroCopyByteRecord::new(
+                    &self.raw_headers.1,
+                    &self.raw_headers.0,
+                    self.inner.delimiter,
+                    self.inner.terminator(),
+                    self.inner.quoting(),
+                    self.inner.trim,
+                )
+                .with_position(self.headers_position),
+            ));
+        }
+
+        self.read_byte_record_impl().await
+    }
+
+    /// Returns the current byte offset of the reader in the wrapped stream.
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
+        if self.must_reemit_headers {
+            self.tracker.record_position().byte
+        } else {
+            self.buffer.position()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_zero_copy_byte_record() -> error::Result<()> {
+        let csv = "name,surname,age\n\"john\",\"landy, the \"\"everlasting\"\" bastard\",45\nlucy,rose,\"67\"\n\njermaine,jackson,\"89\"\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut sync_reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_reader(csv.as_bytes());
+
+            let mut expected = Vec::new();
+
+            while let Some(record) = sync_reader.read_byte_record()? {
+                expected.push((record.to_byte_record(), sync_reader.position()));
+            }
+
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_async_reader(csv.as_bytes());
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record().await? {
+                records.push((record.to_byte_record(), reader.position()));
+            }
+
+            assert_eq!(records, expected);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_byte_headers() -> error::Result<()> {
+        let data = "# metadata\nname,surname\n john , dandy \n";
+
+        let mut reader = ZeroCopyReaderBuilder::new()
+            .skip_lines(1)
+            .trim(Trim::All)
+            .from_async_reader(data.as_bytes());
+
+        assert_eq!(reader.preamble().await?, b"# metadata\n");
+        assert_eq!(reader.byte_headers().await?, &brec!["name", "surname"]);
+        assert_eq!(
+            reader.read_byte_record().await?.unwrap().to_byte_record(),
+            brec!["john", "dandy"]
+        );
+        assert!(reader.read_byte_record().await?.is_none());
+
+        let mut reader = AsyncZeroCopyReader::from_reader("".as_bytes());
+        assert_eq!(reader.byte_headers().await?, &brec![]);
+        assert!(reader.read_byte_record().await?.is_none());
+
+        Ok(())
+    }
+}
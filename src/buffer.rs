@@ -1,23 +1,46 @@
-use std::io::{BufRead, BufReader, Read, Result};
+use std::io::{BufRead, BufReader, Read, Result, Seek, SeekFrom};
 
+/// A buffered reader, sync or async, whose buffered bytes can be accessed and
+/// consumed without any IO, so that the bookkeeping of [`Positioned`] and
+/// [`Scratch`] can be shared by the sync & async readers.
+pub trait Buffered {
+    fn buffer(&self) -> &[u8];
+    fn consume(&mut self, amt: usize);
+}
+
+impl<R: Read> Buffered for BufReader<R> {
+    #[inline(always)]
+    fn buffer(&self) -> &[u8] {
+        BufReader::buffer(self)
+    }
+
+    #[inline(always)]
+    fn consume(&mut self, amt: usize) {
+        BufRead::consume(self, amt);
+    }
+}
+
+/// Keeps track of the position of a buffered reader in the stream, and lets
+/// some consumed bytes be given back to it.
 #[derive(Debug)]
-pub struct BufReaderWithPosition<R> {
-    inner: BufReader<R>,
+pub struct Positioned<B> {
+    inner: B,
     pos: u64,
+    // NOTE: bytes given back using `unread`, that must be read before the
+    // inner buffer
+    pending: Vec<u8>,
+    pending_pos: usize,
 }
 
-impl<R: Read> BufReaderWithPosition<R> {
-    pub fn new(reader: R) -> Self {
-        Self {
-            inner: BufReader::new(reader),
-            pos: 0,
-        }
-    }
+pub type BufReaderWithPosition<R> = Positioned<BufReader<R>>;
 
-    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+impl<B: Buffered> Positioned<B> {
+    pub(crate) fn wrap(inner: B) -> Self {
         Self {
-            inner: BufReader::with_capacity(capacity, reader),
+            inner,
             pos: 0,
+            pending: Vec::new(),
+            pending_pos: 0,
         }
     }
 
@@ -29,11 +52,72 @@ impl<R: Read> BufReaderWithPosition<R> {
     #[inline(always)]
     pub fn consume(&mut self, amt: usize) {
         self.pos += amt as u64;
-        self.inner.consume(amt);
+
+        if self.pending.is_empty() {
+            self.inner.consume(amt);
+        } else {
+            self.consume_pending(amt);
+        }
+    }
+
+    #[cold]
+    fn consume_pending(&mut self, amt: usize) {
+        self.pending_pos += amt;
+
+        if self.pending_pos >= self.pending.len() {
+            self.pending.clear();
+            self.pending_pos = 0;
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Give back some bytes that were just consumed so they can be read again.
+    pub fn unread(&mut self, bytes: &[u8]) {
+        debug_assert!(self.pending.is_empty());
+
+        self.pos -= bytes.len() as u64;
+        self.pending.extend_from_slice(bytes);
+    }
+
+    #[inline(always)]
+    pub fn buffer(&self) -> &[u8] {
+        if !self.pending.is_empty() {
+            return &self.pending[self.pending_pos..];
+        }
+
+        self.inner.buffer()
+    }
+
+    #[cfg(feature = "tokio")]
+    #[inline(always)]
+    pub(crate) fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<R: Read> BufReaderWithPosition<R> {
+    pub fn new(reader: R) -> Self {
+        Self::wrap(BufReader::new(reader))
+    }
+
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self::wrap(BufReader::with_capacity(capacity, reader))
     }
 
     #[inline(always)]
     pub fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.has_pending() {
+            return Ok(self.buffer());
+        }
+
         self.inner.fill_buf()
     }
 
@@ -46,46 +130,50 @@ impl<R: Read> BufReaderWithPosition<R> {
     pub fn get_ref(&self) -> &R {
         self.inner.get_ref()
     }
+}
 
-    #[inline(always)]
-    pub fn buffer(&self) -> &[u8] {
-        self.inner.buffer()
-    }
+impl<R: Read + Seek> BufReaderWithPosition<R> {
+    /// Seek to the given position, relative to the beginning of the stream as
+    /// reported by [`Self::position`], discarding any buffered data.
+    pub fn seek(&mut self, pos: u64) -> Result<()> {
+        // NOTE: the inner reader stands after any pending bytes
+        let current = self.pos + (self.pending.len() - self.pending_pos) as u64;
 
-    pub fn into_inner(self) -> BufReader<R> {
         self.inner
+            .seek(SeekFrom::Current(pos as i64 - current as i64))?;
+
+        self.pending.clear();
+        self.pending_pos = 0;
+        self.pos = pos;
+
+        Ok(())
     }
 }
 
+/// A [`Positioned`] buffer that can save bytes in a scratch space, so that
+/// records spanning multiple reads of the inner buffer can still be handed out
+/// as a single slice.
 #[derive(Debug)]
-pub struct ScratchBuffer<R> {
-    inner: BufReaderWithPosition<R>,
+pub struct Scratch<B> {
+    inner: Positioned<B>,
     scratch: Vec<u8>,
     next_consume: Option<usize>,
 }
 
-impl<R: Read> ScratchBuffer<R> {
-    pub fn new(reader: R) -> Self {
-        Self {
-            inner: BufReaderWithPosition::new(reader),
-            scratch: Vec::new(),
-            next_consume: None,
-        }
-    }
+pub type ScratchBuffer<R> = Scratch<BufReader<R>>;
 
-    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+impl<B: Buffered> Scratch<B> {
+    pub(crate) fn wrap(inner: Positioned<B>, capacity: usize) -> Self {
         Self {
-            inner: BufReaderWithPosition::with_capacity(capacity, reader),
+            inner,
             scratch: Vec::with_capacity(capacity),
             next_consume: None,
         }
     }
 
-    pub(crate) fn with_optional_capacity(capacity: Option<usize>, reader: R) -> Self {
-        match capacity {
-            None => Self::new(reader),
-            Some(capacity) => Self::with_capacity(capacity, reader),
-        }
+    #[inline(always)]
+    pub(crate) fn inner_mut(&mut self) -> &mut Positioned<B> {
+        &mut self.inner
     }
 
     #[inline(always)]
@@ -93,9 +181,11 @@ impl<R: Read> ScratchBuffer<R> {
         self.inner.consume(amt);
     }
 
+    /// Returns the bytes currently buffered, i.e. the ones last returned when
+    /// filling the buffer.
     #[inline(always)]
-    pub fn fill_buf(&mut self) -> Result<&[u8]> {
-        self.inner.fill_buf()
+    pub fn buffer(&self) -> &[u8] {
+        self.inner.buffer()
     }
 
     #[inline(always)]
@@ -141,14 +231,57 @@ impl<R: Read> ScratchBuffer<R> {
         }
     }
 
+    /// Returns the bytes given by the last call to [`Self::flush`].
+    #[inline(always)]
+    pub fn flushed(&self) -> &[u8] {
+        match self.next_consume {
+            Some(amt) => &self.inner.buffer()[..amt],
+            None => &self.scratch,
+        }
+    }
+
     #[inline(always)]
     pub fn position(&self) -> u64 {
         let offset = self.next_consume.unwrap_or(0) as u64;
         self.inner.position() + offset
     }
+}
+
+impl<R: Read> ScratchBuffer<R> {
+    pub fn new(reader: R) -> Self {
+        Self::wrap(BufReaderWithPosition::new(reader), 0)
+    }
+
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self::wrap(
+            BufReaderWithPosition::with_capacity(capacity, reader),
+            capacity,
+        )
+    }
+
+    pub(crate) fn with_optional_capacity(capacity: Option<usize>, reader: R) -> Self {
+        match capacity {
+            None => Self::new(reader),
+            Some(capacity) => Self::with_capacity(capacity, reader),
+        }
+    }
+
+    #[inline(always)]
+    pub fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
 
     pub(crate) fn into_bufreader(mut self) -> BufReader<R> {
         self.reset();
         self.inner.into_inner()
     }
 }
+
+impl<R: Read + Seek> ScratchBuffer<R> {
+    /// Seek to the given position, discarding any buffered or saved data.
+    pub fn seek(&mut self, pos: u64) -> Result<()> {
+        self.scratch.clear();
+        self.next_consume = None;
+        self.inner.seek(pos)
+    }
+}
@@ -0,0 +1,400 @@
+use alloc::vec::Vec;
+
+use crate::core::{CoreReader, ReadResult};
+use crate::dialect::{Delimiter, Dialect, Terminator};
+use crate::error::{self, Error, ErrorKind};
+use crate::position::Position;
+use crate::records::{ByteRecord, ByteRecordBuilder};
+
+const MAGIC: &[u8; 8] = b"SIMDCSV\x01";
+
+/// A snapshot of the state of a reader, taken between two records, that can
+/// be used to resume reading the same stream later on, e.g. in another
+/// process.
+///
+/// Checkpoints can be taken using [`Reader::checkpoint`](crate::Reader::checkpoint),
+/// [`ZeroCopyReader::checkpoint`](crate::ZeroCopyReader::checkpoint) or
+/// [`Splitter::checkpoint`](crate::Splitter::checkpoint), persisted using
+/// [`Self::to_bytes`] and [`Self::from_bytes`], then given to
+/// [`ReaderBuilder::resume_from_reader`](crate::ReaderBuilder::resume_from_reader),
+/// [`ZeroCopyReaderBuilder::resume_from_reader`](crate::ZeroCopyReaderBuilder::resume_from_reader)
+/// or [`SplitterBuilder::resume_from_reader`](crate::SplitterBuilder::resume_from_reader).
+///
+/// A checkpoint records the position of the next record to read, the headers
+/// and the dialect (delimiter, quote, terminator etc.) of the reader it was
+/// taken from. Other settings, such as flexibility, trimming or limits, are
+/// not recorded and are taken from the builder used to resume reading.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    position: Position,
+    headers: ByteRecord,
+    has_headers: bool,
+    dialect: Dialect,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(
+        position: Position,
+        headers: ByteRecord,
+        has_headers: bool,
+        mut dialect: Dialect,
+    ) -> Self {
+        // NOTE: preamble lines are never read again when resuming
+        dialect.skip_lines = Default::default();
+
+        Self {
+            position,
+            headers,
+            has_headers,
+            dialect,
+        }
+    }
+
+    /// Returns the position of the next record to read.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the headers of the checkpointed reader.
+    pub fn byte_headers(&self) -> &ByteRecord {
+        &self.headers
+    }
+
+    /// Returns whether the checkpointed reader was configured to interpret
+    /// the first record as a header.
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    pub(crate) fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// Serialize the checkpoint into bytes that can be given back to
+    /// [`Self::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let dialect = &self.dialect;
+        let mut bytes = MAGIC.to_vec();
+
+        for n in [self.position.byte, self.position.line, self.position.record] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+
+        bytes.push(self.has_headers as u8);
+
+        bytes.push(dialect.delimiter.len() as u8);
+        bytes.extend_from_slice(dialect.delimiter.as_bytes());
+        bytes.push(dialect.quote);
+        bytes.push(dialect.quoting as u8);
+        push_optional_byte(&mut bytes, dialect.escape);
+        bytes.push(dialect.double_quote as u8);
+        push_optional_byte(&mut bytes, dialect.comment);
+        push_optional_byte(
+            &mut bytes,
+            match dialect.terminator {
+                Terminator::CRLF => None,
+                Terminator::Any(byte) => Some(byte),
+            },
+        );
+        bytes.push(dialect.strict as u8);
+        bytes.push(dialect.lenient_quotes as u8);
+        bytes.push(dialect.skip_empty_lines as u8);
+
+        bytes.extend_from_slice(&(self.headers.len() as u64).to_le_bytes());
+
+        for cell in self.headers.iter() {
+            bytes.extend_from_slice(&(cell.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(cell);
+        }
+
+        bytes
+    }
+
+    /// Deserialize a checkpoint from bytes produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> error::Result<Self> {
+        let mut decoder = Decoder { bytes };
+
+        if decoder.take(MAGIC.len())? != MAGIC {
+            return Err(invalid());
+        }
+
+        let position = Position {
+            byte: decoder.u64()?,
+            line: decoder.u64()?,
+            record: decoder.u64()?,
+        };
+
+        let has_headers = decoder.bool()?;
+
+        let delimiter_len = decoder.byte()? as usize;
+
+        if delimiter_len == 0 || delimiter_len > Delimiter::MAX_LEN {
+            return Err(invalid());
+        }
+
+        let dialect = Dialect {
+            delimiter: Delimiter::new(decoder.take(delimiter_len)?),
+            quote: decoder.byte()?,
+            quoting: decoder.bool()?,
+            escape: decoder.optional_byte()?,
+            double_quote: decoder.bool()?,
+            comment: decoder.optional_byte()?,
+            terminator: match decoder.optional_byte()? {
+                None => Terminator::CRLF,
+                Some(byte) => Terminator::Any(byte),
+            },
+            strict: decoder.bool()?,
+            lenient_quotes: decoder.bool()?,
+            skip_empty_lines: decoder.bool()?,
+            skip_lines: Default::default(),
+        };
+
+        let mut headers = ByteRecord::new();
+
+        for _ in 0..decoder.u64()? {
+            let len = decoder.u64()?;
+            let len = usize::try_from(len).map_err(|_| invalid())?;
+
+            headers.push_field(decoder.take(len)?);
+        }
+
+        if !decoder.bytes.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            position,
+            headers,
+            has_headers,
+            dialect,
+        })
+    }
+
+    /// Encode the headers as a raw CSV line, as a [`Splitter`](crate::Splitter)
+    /// would have found it, along with the positions of its separators.
+    pub(crate) fn raw_headers(&self) -> (Vec<usize>, Vec<u8>) {
+        let dialect = &self.dialect;
+        let mut seps = Vec::new();
+        let mut raw = Vec::new();
+
+        for (i, cell) in self.headers.iter().enumerate() {
+            if i > 0 {
+                seps.push(raw.len());
+                raw.extend_from_slice(dialect.delimiter.as_bytes());
+            }
+
+            let must_quote = dialect.quoting
+                && cell.iter().any(|byte| {
+                    *byte == dialect.quote
+                        || *byte == dialect.delimiter.first()
+                        || *byte == dialect.terminator.byte()
+                        || *byte == b'\r'
+                });
+
+            if !must_quote {
+                raw.extend_from_slice(cell);
+                continue;
+            }
+
+            raw.push(dialect.quote);
+
+            for byte in cell {
+                if *byte == dialect.quote || Some(*byte) == dialect.escape {
+                    raw.push(dialect.escape.unwrap_or(dialect.quote));
+                }
+
+                raw.push(*byte);
+            }
+
+            raw.push(dialect.quote);
+        }
+
+        (seps, raw)
+    }
+}
+
+/// Parse a raw CSV line, as returned by a [`Splitter`](crate::Splitter), into
+/// a [`ByteRecord`].
+pub(crate) fn parse_raw_record(dialect: &Dialect, raw: &[u8]) -> ByteRecord {
+    let mut record = ByteRecord::new();
+    let mut core = CoreReader::new(dialect);
+    let mut record_builder = ByteRecordBuilder::wrap(&mut record);
+
+    // NOTE: the record is complete once the reader reaches the end of input
+    if let (ReadResult::InputEmpty, _) = core.read_record(raw, &mut record_builder) {
+        core.read_record(&[], &mut record_builder);
+    }
+
+    record
+}
+
+fn push_optional_byte(bytes: &mut Vec<u8>, byte: Option<u8>) {
+    match byte {
+        None => bytes.extend_from_slice(&[0, 0]),
+        Some(byte) => bytes.extend_from_slice(&[1, byte]),
+    }
+}
+
+fn invalid() -> Error {
+    Error::new(ErrorKind::InvalidCheckpoint)
+}
+
+struct Decoder<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Decoder<'b> {
+    fn take(&mut self, len: usize) -> error::Result<&'b [u8]> {
+        if self.bytes.len() < len {
+            return Err(invalid());
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> error::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> error::Result<bool> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn optional_byte(&mut self) -> error::Result<Option<u8>> {
+        let is_some = self.bool()?;
+        let byte = self.byte()?;
+
+        Ok(is_some.then_some(byte))
+    }
+
+    fn u64(&mut self) -> error::Result<u64> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+
+        Ok(u64::from_le_bytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    use crate::{ReaderBuilder, SplitterBuilder, ZeroCopyReaderBuilder};
+
+    const DATA: &str = "# metadata\nname,\"a \"\"quoted\"\", header\"\njohn,\"multi\nline\"\n\nlucy,rose\r\nbob,\"a\nb\"\nlast,one\n";
+
+    #[test]
+    fn test_to_bytes() -> error::Result<()> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter_bytes(b"||")
+            .escape(Some(b'\\'))
+            .comment(Some(b'#'))
+            .terminator(Terminator::Any(b';'))
+            .from_reader("name||surname;john||\"landy\\\"\";lucy||rose".as_bytes());
+
+        reader.byte_records().next().unwrap()?;
+
+        let checkpoint = reader.checkpoint()?;
+        let bytes = checkpoint.to_bytes();
+        let decoded = Checkpoint::from_bytes(&bytes)?;
+
+        assert_eq!(decoded.position(), checkpoint.position());
+        assert_eq!(decoded.byte_headers(), &brec!["name", "surname"]);
+        assert!(decoded.has_headers());
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.dialect().delimiter.as_bytes(), b"||");
+        assert_eq!(decoded.dialect().terminator, Terminator::Any(b';'));
+
+        for invalid in [
+            &bytes[..bytes.len() - 1],
+            &[bytes.as_slice(), b"!"].concat(),
+            b"test",
+        ] {
+            assert!(matches!(
+                Checkpoint::from_bytes(invalid).unwrap_err().kind(),
+                ErrorKind::InvalidCheckpoint
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume() -> error::Result<()> {
+        for capacity in [32usize, 4, 3, 2, 1] {
+            for has_headers in [true, false] {
+                let mut builder = ReaderBuilder::with_capacity(capacity);
+                builder.skip_lines(1).has_headers(has_headers);
+
+                let expected = builder
+                    .from_reader(DATA.as_bytes())
+                    .byte_records()
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for skip in 0..=expected.len() {
+                    let mut reader = builder.from_reader(Cursor::new(DATA));
+                    let mut splitter = SplitterBuilder::with_capacity(capacity)
+                        .skip_lines(1)
+                        .has_headers(has_headers)
+                        .from_reader(Cursor::new(DATA));
+
+                    for _ in 0..skip {
+                        reader.byte_records().next().unwrap()?;
+                        splitter.split_record()?;
+                    }
+
+                    let checkpoint = Checkpoint::from_bytes(&reader.checkpoint()?.to_bytes())?;
+                    let mut resumed = ReaderBuilder::with_capacity(capacity)
+                        .resume_from_reader(Cursor::new(DATA), &checkpoint)?;
+
+                    assert_eq!(resumed.byte_headers()?, reader.byte_headers()?);
+                    assert_eq!(
+                        resumed.byte_records().collect::<Result<Vec<_>, _>>()?,
+                        &expected[skip..]
+                    );
+                    assert_eq!(resumed.byte_records().count(), 0);
+
+                    let checkpoint = splitter.checkpoint()?;
+                    assert_eq!(checkpoint.byte_headers(), reader.byte_headers()?);
+                    assert_eq!(checkpoint.position(), reader.checkpoint()?.position());
+
+                    let mut resumed = SplitterBuilder::with_capacity(capacity)
+                        .resume_from_reader(Cursor::new(DATA), &checkpoint)?;
+
+                    assert_eq!(resumed.byte_headers()?, splitter.byte_headers()?);
+                    assert_eq!(resumed.checkpoint()?.position(), checkpoint.position());
+
+                    let mut resumed = ZeroCopyReaderBuilder::with_capacity(capacity)
+                        .resume_from_reader(Cursor::new(DATA), &checkpoint)?;
+                    let mut records = Vec::new();
+                    let mut positions = Vec::new();
+
+                    while let Some(record) = resumed.read_byte_record()? {
+                        records.push(record.to_byte_record());
+                        positions.push(record.position().copied());
+                    }
+
+                    assert_eq!(records, &expected[skip..]);
+                    assert_eq!(
+                        positions,
+                        expected[skip..]
+                            .iter()
+                            .map(|record| record.position().copied())
+                            .collect::<Vec<_>>()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,21 @@
+use alloc::vec::Vec;
+
 use memchr::{memchr, memchr2};
 
-use crate::records::ByteRecordBuilder;
+use crate::dialect::{Delimiter, Dialect, Quoting, Terminator};
+use crate::records::{ByteRecordBuilder, ZeroCopyByteRecord};
 use crate::searcher::Searcher;
+use crate::utils::{count_lines, trim_trailing_crlf};
 
 #[derive(Debug, Clone, Copy)]
 pub enum ReadResult {
     InputEmpty,
     Cr,
     Lf,
+    Comment,
     Record,
+    MalformedQuote,
+    UnterminatedQuote,
     End,
 }
 
@@ -16,29 +23,411 @@ pub enum ReadResult {
 enum ReadState {
     Unquoted,
     Quoted,
+    QuotedEscape,
     Quote,
+    // NOTE: only used in strict mode, when the bytes following a closing quote
+    // must be checked across calls, the given number of bytes of a multi-byte
+    // delimiter having been seen already
+    QuoteDelimiter(usize),
+    // NOTE: same, but a CR was seen and must be followed by a LF
+    QuoteCr,
+    // NOTE: when quotes are not doubled, a quote following a closing quote is
+    // literal, as is the rest of the field, the way the csv crate reads it
+    LiteralTail,
 }
 
 // NOTE: funnily enough, knowing the delimiter is not required to split the records,
 // but since we expose a single unified `struct` here, it is simpler to include it.
 #[derive(Debug)]
 pub(crate) struct CoreReader {
-    pub(crate) delimiter: u8,
+    pub(crate) delimiter: Delimiter,
     pub(crate) quote: u8,
+    pub(crate) quoting_enabled: bool,
+    pub(crate) escape: Option<u8>,
+    pub(crate) double_quote: bool,
+    pub(crate) trim: bool,
+    newline: u8,
+    crlf: bool,
+    strict: bool,
+    lenient_quotes: bool,
+    skip_empty_lines: bool,
+    // NOTE: last bytes seen during the previous call, the very last one
+    // being at the end, only kept when field starts must be known
+    last_bytes: [u8; Delimiter::MAX_LEN],
+    tracks_field_starts: bool,
+    comment: Option<u8>,
     state: ReadState,
     record_was_read: bool,
+    in_comment: bool,
+    ended_in_quote: bool,
+    // NOTE: whether a quoted field was entered since the last call to
+    // `lines_read`
+    entered_quotes: bool,
     searcher: Searcher,
+    // NOTE: only used to read records the slow way, see `read_record_buffered`
+    scratch: Vec<u8>,
+    seps: Vec<usize>,
 }
 
 impl CoreReader {
-    pub(crate) fn new(delimiter: u8, quote: u8) -> Self {
+    pub(crate) fn new(dialect: &Dialect) -> Self {
         Self {
-            delimiter,
-            quote,
+            delimiter: dialect.delimiter,
+            quote: dialect.quote,
+            quoting_enabled: dialect.quoting,
+            escape: dialect.escape,
+            double_quote: dialect.double_quote,
+            trim: false,
+            newline: dialect.terminator.byte(),
+            crlf: dialect.terminator == Terminator::CRLF,
+            strict: dialect.strict,
+            lenient_quotes: dialect.lenient_quotes,
+            skip_empty_lines: dialect.skip_empty_lines,
+            last_bytes: [dialect.terminator.byte(); Delimiter::MAX_LEN],
+            tracks_field_starts: dialect.strict || dialect.lenient_quotes || !dialect.double_quote,
+            comment: dialect.comment,
             state: ReadState::Unquoted,
             // Must be true at the beginning to avoid counting one record for empty input
             record_was_read: true,
-            searcher: Searcher::new(delimiter, b'\n', quote),
+            in_comment: false,
+            ended_in_quote: false,
+            entered_quotes: false,
+            // NOTE: when quoting is disabled, the delimiter is searched twice
+            // so that quotes are never found.
+            searcher: Searcher::new(
+                dialect.delimiter.first(),
+                dialect.terminator.byte(),
+                if dialect.quoting {
+                    dialect.quote
+                } else {
+                    dialect.delimiter.first()
+                },
+            ),
+            scratch: Vec::new(),
+            seps: Vec::new(),
+        }
+    }
+
+    /// Returns the dialect this reader was created with, save for the
+    /// preamble lines to skip.
+    #[cfg(feature = "std")]
+    pub(crate) fn dialect(&self) -> Dialect {
+        Dialect {
+            delimiter: self.delimiter,
+            quote: self.quote,
+            quoting: self.quoting_enabled,
+            escape: self.escape,
+            double_quote: self.double_quote,
+            comment: self.comment,
+            terminator: self.terminator(),
+            strict: self.strict,
+            lenient_quotes: self.lenient_quotes,
+            skip_empty_lines: self.skip_empty_lines,
+            skip_lines: Default::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn newline(&self) -> u8 {
+        self.newline
+    }
+
+    #[inline]
+    pub(crate) fn terminator(&self) -> Terminator {
+        if self.crlf {
+            Terminator::CRLF
+        } else {
+            Terminator::Any(self.newline)
+        }
+    }
+
+    /// Put the reader in the state it would be in right after a newline found
+    /// within a quoted field, so that some arbitrary chunk of the input can be
+    /// split speculatively.
+    #[cfg(feature = "std")]
+    pub(crate) fn start_in_quoted_field(&mut self) {
+        self.state = ReadState::Quoted;
+        self.entered_quotes = true;
+        self.record_was_read = false;
+        self.forget_last_bytes();
+    }
+
+    /// Put the reader back in its initial state, e.g. after seeking to the
+    /// beginning of some record.
+    #[cfg(feature = "std")]
+    pub(crate) fn reset(&mut self) {
+        self.state = ReadState::Unquoted;
+        self.record_was_read = true;
+        self.forget_last_bytes();
+        self.in_comment = false;
+        self.ended_in_quote = false;
+        self.entered_quotes = false;
+        self.scratch.clear();
+        self.seps.clear();
+    }
+
+    /// Returns the number of lines spanned by the given bytes, which must be
+    /// the ones read since the last call.
+    ///
+    /// Outside of quoted fields, newlines always end what the reader returns,
+    /// so the bytes are only scanned when a quoted field was entered, or when
+    /// the reader still stands within one.
+    #[inline(always)]
+    pub(crate) fn lines_read(&mut self, read: &[u8]) -> u64 {
+        let entered_quotes = self.entered_quotes;
+        self.entered_quotes = !matches!(self.state, ReadState::Unquoted);
+
+        if entered_quotes {
+            count_lines(read, self.newline)
+        } else {
+            (read.last() == Some(&self.newline)) as u64
+        }
+    }
+
+    /// Returns whether the reader currently stands within a quoted field.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn is_in_quoted_field(&self) -> bool {
+        matches!(self.state, ReadState::Quoted | ReadState::QuotedEscape)
+    }
+
+    pub(crate) fn quoting(&self) -> Quoting {
+        Quoting {
+            enabled: self.quoting_enabled,
+            quote: self.quote,
+            escape: self.escape,
+            double_quote: self.double_quote,
+            lenient: self.lenient_quotes,
+        }
+    }
+
+    /// Trims the record terminator from the end of the given slice.
+    ///
+    /// A record ending the stream in the middle of a quoted field has no
+    /// terminator, the newlines it ends with being part of the field.
+    #[inline]
+    pub(crate) fn trim_terminator<'s>(&self, slice: &'s [u8]) -> &'s [u8] {
+        if self.ended_in_quote {
+            slice
+        } else if self.crlf {
+            trim_trailing_crlf(slice)
+        } else if slice.last() == Some(&self.newline) {
+            &slice[..slice.len() - 1]
+        } else {
+            slice
+        }
+    }
+
+    #[inline(always)]
+    fn finalize_record(&self, record_builder: &mut ByteRecordBuilder) {
+        if self.crlf {
+            record_builder.finalize_record();
+        } else {
+            record_builder.finalize_field();
+        }
+    }
+
+    #[inline(always)]
+    fn forget_last_bytes(&mut self) {
+        if !self.tracks_field_starts {
+            return;
+        }
+
+        self.last_bytes = [self.newline; Delimiter::MAX_LEN];
+    }
+
+    #[inline(always)]
+    fn remember_last_bytes(&mut self, input: &[u8]) {
+        if !self.tracks_field_starts {
+            return;
+        }
+
+        let n = input.len().min(Delimiter::MAX_LEN);
+
+        self.last_bytes.copy_within(n.., 0);
+        self.last_bytes[Delimiter::MAX_LEN - n..].copy_from_slice(&input[input.len() - n..]);
+    }
+
+    // NOTE: the bytes preceding the first one of the input were seen during
+    // the last call, which is why we need to remember them. A preceding
+    // newline means we are at the beginning of a record.
+    #[inline(always)]
+    fn is_field_start(&self, input: &[u8], i: usize) -> bool {
+        let prev = if i > 0 {
+            input[i - 1]
+        } else {
+            self.last_bytes[Delimiter::MAX_LEN - 1]
+        };
+
+        if prev == self.newline {
+            return true;
+        }
+
+        prev == self.delimiter.last()
+            && (!self.delimiter.is_multi_byte() || self.is_after_multi_byte_delimiter(input, i))
+    }
+
+    // NOTE: the whole delimiter must end right before `i`, not only its last
+    // byte, some of its bytes possibly having been seen during the last call.
+    #[inline]
+    fn is_after_multi_byte_delimiter(&self, input: &[u8], i: usize) -> bool {
+        let delimiter = self.delimiter.as_bytes();
+        let len = delimiter.len();
+
+        if i >= len {
+            return &input[i - len..i] == delimiter;
+        }
+
+        let missing = len - i;
+
+        self.last_bytes[Delimiter::MAX_LEN - missing..] == delimiter[..missing]
+            && input[..i] == delimiter[missing..]
+    }
+
+    // NOTE: in lenient mode, quotes are only meaningful at the beginning of a
+    // field and must be considered as any other byte elsewhere.
+    #[inline(always)]
+    fn is_literal_quote(&self, input: &[u8], i: usize) -> bool {
+        self.lenient_quotes && !self.is_field_start(input, i)
+    }
+
+    /// Returns whether the end of the input was reached in the middle of a
+    /// quoted field, whether the reader is strict or not.
+    #[inline]
+    pub(crate) fn ended_in_quote(&self) -> bool {
+        self.ended_in_quote
+    }
+
+    // NOTE: the state is reset so that subsequent calls will report the end
+    // of the stream, but the fact it ended in a quoted field is remembered.
+    #[inline]
+    fn is_unterminated(&mut self) -> bool {
+        self.ended_in_quote = matches!(self.state, ReadState::Quoted | ReadState::QuotedEscape);
+        self.state = ReadState::Unquoted;
+
+        self.strict && self.ended_in_quote
+    }
+
+    // NOTE: the rest of the line is skipped as if it were a comment, so that
+    // reading can resume with the next one after the error was reported.
+    #[cold]
+    fn malformed_quote(&mut self, pos: usize) -> (ReadResult, usize) {
+        self.state = ReadState::Unquoted;
+        self.record_was_read = true;
+        self.in_comment = true;
+
+        (ReadResult::MalformedQuote, pos)
+    }
+
+    // NOTE: in strict mode, a closing quote must be followed by the whole
+    // delimiter or by a terminator, which might only be known during next
+    // calls, hence the states where the remaining bytes are expected.
+    #[inline]
+    fn check_after_quote(&mut self, byte: u8) -> bool {
+        self.state = if byte == self.delimiter.first() {
+            if self.delimiter.is_multi_byte() {
+                ReadState::QuoteDelimiter(1)
+            } else {
+                ReadState::Unquoted
+            }
+        } else if self.crlf && byte == b'\r' {
+            ReadState::QuoteCr
+        } else {
+            return false;
+        };
+
+        true
+    }
+
+    // NOTE: returns the number of bytes read, or `None` if the expected byte
+    // was not found. A LF is left for the unquoted state to handle.
+    #[inline]
+    fn read_after_quote(&mut self, byte: u8) -> Option<usize> {
+        match self.state {
+            ReadState::QuoteDelimiter(seen) => {
+                if byte != self.delimiter.as_bytes()[seen] {
+                    return None;
+                }
+
+                self.state = if seen + 1 < self.delimiter.len() {
+                    ReadState::QuoteDelimiter(seen + 1)
+                } else {
+                    ReadState::Unquoted
+                };
+
+                Some(1)
+            }
+            _ => {
+                if byte != b'\n' {
+                    return None;
+                }
+
+                self.state = ReadState::Unquoted;
+
+                Some(0)
+            }
+        }
+    }
+
+    // NOTE: a quote following a closing quote is an escaped quote, unless
+    // quotes are not doubled, in which case it starts the literal tail of the
+    // field, which strict mode does not allow.
+    #[inline]
+    fn read_quote_after_quote(&mut self) -> bool {
+        self.state = if self.double_quote {
+            ReadState::Quoted
+        } else if self.strict {
+            return false;
+        } else {
+            ReadState::LiteralTail
+        };
+
+        true
+    }
+
+    #[inline]
+    fn is_truncated_after_quote(&self) -> bool {
+        matches!(
+            self.state,
+            ReadState::QuoteDelimiter(_) | ReadState::QuoteCr
+        )
+    }
+
+    // Returns the offset of the next newline, or quote if quoting is enabled,
+    // in unquoted data
+    #[inline(always)]
+    fn find_in_unquoted(&self, haystack: &[u8]) -> Option<usize> {
+        if self.quoting_enabled {
+            memchr2(self.newline, self.quote, haystack)
+        } else {
+            memchr(self.newline, haystack)
+        }
+    }
+
+    // Returns the offset of the next quote, or escape char if any, in quoted data
+    #[inline(always)]
+    fn find_in_quoted(&self, haystack: &[u8]) -> Option<usize> {
+        match self.escape {
+            None => memchr(self.quote, haystack),
+            Some(escape) => memchr2(self.quote, escape, haystack),
+        }
+    }
+
+    // NOTE: comment lines are only recognized at the very beginning of a
+    // record and are skipped until next newline, without any regard for quoting.
+    // Since `record_was_read` remains true while skipping, comment lines cannot
+    // be mistaken for records, even when the stream ends in the middle of one.
+    #[inline]
+    fn skip_comment(&mut self, input: &[u8]) -> (ReadResult, usize) {
+        match memchr(self.newline, input) {
+            Some(offset) => {
+                self.in_comment = false;
+                (ReadResult::Comment, offset + 1)
+            }
+            None => {
+                self.in_comment = true;
+                (ReadResult::Comment, input.len())
+            }
         }
     }
 
@@ -49,7 +438,16 @@ impl CoreReader {
 
         if input_len == 0 {
             if !self.record_was_read {
+                if self.is_truncated_after_quote() {
+                    return self.malformed_quote(0);
+                }
+
                 self.record_was_read = true;
+
+                if self.is_unterminated() {
+                    return (ReadResult::UnterminatedQuote, 0);
+                }
+
                 return (ReadResult::Record, 0);
             }
 
@@ -57,11 +455,21 @@ impl CoreReader {
         }
 
         if self.record_was_read {
-            if input[0] == b'\n' {
+            if self.in_comment || self.comment == Some(input[0]) {
+                return self.skip_comment(input);
+            }
+
+            if input[0] == self.newline {
+                if !self.skip_empty_lines {
+                    return (ReadResult::Record, 1);
+                }
+
                 return (ReadResult::Lf, 1);
-            } else if input[0] == b'\r' {
+            } else if self.crlf && input[0] == b'\r' {
                 return (ReadResult::Cr, 1);
             }
+
+            self.forget_last_bytes();
         }
 
         self.record_was_read = false;
@@ -72,62 +480,118 @@ impl CoreReader {
             match self.state {
                 Unquoted => {
                     // Fast path for quoted field start
-                    if input[pos] == self.quote {
+                    if self.quoting_enabled
+                        && input[pos] == self.quote
+                        && !self.is_literal_quote(input, pos)
+                    {
+                        if self.strict && !self.is_field_start(input, pos) {
+                            return self.malformed_quote(pos);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                         pos += 1;
                         continue;
                     }
 
                     // Here we are moving to next quote or end of line
-                    if let Some(offset) = memchr2(b'\n', self.quote, &input[pos..]) {
+                    if let Some(offset) = self.find_in_unquoted(&input[pos..]) {
                         pos += offset;
 
                         let byte = input[pos];
 
                         pos += 1;
 
-                        if byte == b'\n' {
+                        if byte == self.newline {
                             self.record_was_read = true;
                             return (ReadResult::Record, pos);
                         }
 
                         // Here, `byte` is guaranteed to be a quote
+                        if self.is_literal_quote(input, pos - 1) {
+                            continue;
+                        }
+
+                        if self.strict && !self.is_field_start(input, pos - 1) {
+                            return self.malformed_quote(pos - 1);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                     } else {
                         break;
                     }
                 }
                 Quoted => {
-                    // Here we moving to next quote
-                    if let Some(offset) = memchr(self.quote, &input[pos..]) {
+                    // Here we moving to next quote or escape char
+                    if let Some(offset) = self.find_in_quoted(&input[pos..]) {
                         pos += offset + 1;
-                        self.state = Quote;
+
+                        if self.escape == Some(input[pos - 1]) {
+                            self.state = QuotedEscape;
+                        } else {
+                            self.state = Quote;
+                        }
                     } else {
                         break;
                     }
                 }
+                QuotedEscape => {
+                    // Escaped byte is skipped, whatever it may be
+                    pos += 1;
+                    self.state = Quoted;
+                }
                 Quote => {
                     let byte = input[pos];
 
                     pos += 1;
 
                     if byte == self.quote {
-                        self.state = Quoted;
-                    } else if byte == b'\n' {
+                        if !self.read_quote_after_quote() {
+                            return self.malformed_quote(pos - 1);
+                        }
+                    } else if byte == self.newline {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         return (ReadResult::Record, pos);
-                    } else if byte == b'\r' && pos + 1 < input_len && input[pos + 1] == b'\n' {
+                    } else if self.crlf && byte == b'\r' && input.get(pos) == Some(&b'\n') {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         return (ReadResult::Record, pos + 1);
+                    } else if self.strict {
+                        if !self.check_after_quote(byte) {
+                            return self.malformed_quote(pos - 1);
+                        }
                     } else {
                         self.state = Unquoted;
                     }
                 }
+                QuoteDelimiter(_) | QuoteCr => match self.read_after_quote(input[pos]) {
+                    Some(read) => pos += read,
+                    None => return self.malformed_quote(pos),
+                },
+                LiteralTail => {
+                    // Here we are moving to the end of the field, byte per
+                    // byte since this is rare
+                    let byte = input[pos];
+
+                    pos += 1;
+
+                    if byte == self.newline {
+                        self.record_was_read = true;
+                        self.state = Unquoted;
+                        return (ReadResult::Record, pos);
+                    }
+
+                    if self.is_field_start(input, pos) {
+                        self.state = Unquoted;
+                    }
+                }
             }
         }
 
+        self.remember_last_bytes(input);
+
         (ReadResult::InputEmpty, input_len)
     }
 
@@ -143,7 +607,16 @@ impl CoreReader {
 
         if input_len == 0 {
             if !self.record_was_read {
+                if self.is_truncated_after_quote() {
+                    return self.malformed_quote(0);
+                }
+
                 self.record_was_read = true;
+
+                if self.is_unterminated() {
+                    return (ReadResult::UnterminatedQuote, 0);
+                }
+
                 return (ReadResult::Record, 0);
             }
 
@@ -151,11 +624,21 @@ impl CoreReader {
         }
 
         if self.record_was_read {
-            if input[0] == b'\n' {
+            if self.in_comment || self.comment == Some(input[0]) {
+                return self.skip_comment(input);
+            }
+
+            if input[0] == self.newline {
+                if !self.skip_empty_lines {
+                    return (ReadResult::Record, 1);
+                }
+
                 return (ReadResult::Lf, 1);
-            } else if input[0] == b'\r' {
+            } else if self.crlf && input[0] == b'\r' {
                 return (ReadResult::Cr, 1);
             }
+
+            self.forget_last_bytes();
         }
 
         self.record_was_read = false;
@@ -166,8 +649,16 @@ impl CoreReader {
             match self.state {
                 Unquoted => {
                     // Fast path for quoted field start
-                    if input[pos] == self.quote {
+                    if self.quoting_enabled
+                        && input[pos] == self.quote
+                        && !self.is_literal_quote(input, pos)
+                    {
+                        if self.strict && !self.is_field_start(input, pos) {
+                            return self.malformed_quote(pos);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                         pos += 1;
                         continue;
                     }
@@ -180,18 +671,27 @@ impl CoreReader {
 
                         let byte = input[pos + offset];
 
-                        if byte == self.delimiter {
+                        if byte == self.delimiter.first() {
                             seps.push(seps_offset + pos + offset);
                             continue;
                         }
 
-                        if byte == b'\n' {
+                        if byte == self.newline {
                             self.record_was_read = true;
                             return (ReadResult::Record, pos + last_offset);
                         }
 
                         // Here, `byte` is guaranteed to be a quote
+                        if self.is_literal_quote(input, pos + offset) {
+                            continue;
+                        }
+
+                        if self.strict && !self.is_field_start(input, pos + offset) {
+                            return self.malformed_quote(pos + offset);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                         break;
                     }
 
@@ -202,39 +702,85 @@ impl CoreReader {
                     }
                 }
                 Quoted => {
-                    // Here we moving to next quote
-                    if let Some(offset) = memchr(self.quote, &input[pos..]) {
+                    // Here we moving to next quote or escape char
+                    if let Some(offset) = self.find_in_quoted(&input[pos..]) {
                         pos += offset + 1;
-                        self.state = Quote;
+
+                        if self.escape == Some(input[pos - 1]) {
+                            self.state = QuotedEscape;
+                        } else {
+                            self.state = Quote;
+                        }
                     } else {
                         break;
                     }
                 }
+                QuotedEscape => {
+                    // Escaped byte is skipped, whatever it may be
+                    pos += 1;
+                    self.state = Quoted;
+                }
                 Quote => {
                     let byte = input[pos];
 
                     pos += 1;
 
                     if byte == self.quote {
-                        self.state = Quoted;
-                    } else if byte == self.delimiter {
+                        if !self.read_quote_after_quote() {
+                            return self.malformed_quote(pos - 1);
+                        }
+                    } else if byte == self.delimiter.first() {
                         seps.push(seps_offset + pos - 1);
-                        self.state = Unquoted;
-                    } else if byte == b'\n' {
+
+                        if self.strict {
+                            self.check_after_quote(byte);
+                        } else {
+                            self.state = Unquoted;
+                        }
+                    } else if byte == self.newline {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         return (ReadResult::Record, pos);
-                    } else if byte == b'\r' && pos + 1 < input_len && input[pos + 1] == b'\n' {
+                    } else if self.crlf && byte == b'\r' && input.get(pos) == Some(&b'\n') {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         return (ReadResult::Record, pos + 1);
+                    } else if self.strict {
+                        if !self.check_after_quote(byte) {
+                            return self.malformed_quote(pos - 1);
+                        }
                     } else {
                         self.state = Unquoted;
                     }
                 }
+                QuoteDelimiter(_) | QuoteCr => match self.read_after_quote(input[pos]) {
+                    Some(read) => pos += read,
+                    None => return self.malformed_quote(pos),
+                },
+                LiteralTail => {
+                    // Here we are moving to the end of the field, byte per
+                    // byte since this is rare
+                    let byte = input[pos];
+
+                    pos += 1;
+
+                    if byte == self.delimiter.first() {
+                        seps.push(seps_offset + pos - 1);
+                    } else if byte == self.newline {
+                        self.record_was_read = true;
+                        self.state = Unquoted;
+                        return (ReadResult::Record, pos);
+                    }
+
+                    if self.is_field_start(input, pos) {
+                        self.state = Unquoted;
+                    }
+                }
             }
         }
 
+        self.remember_last_bytes(input);
+
         (ReadResult::InputEmpty, input_len)
     }
 
@@ -302,6 +848,55 @@ impl CoreReader {
     //     (ReadResult::InputEmpty, input.len())
     // }
 
+    // NOTE: fields cannot be delimited on the fly when the delimiter spans
+    // multiple bytes, since the rest of it might only be found in the next
+    // input. The same goes for trimming, since we cannot know whether some
+    // whitespace is trailing before reaching the end of the field. So in
+    // those cases we buffer the whole record while collecting separator
+    // candidates, before verifying them and unescaping the fields.
+    fn read_record_buffered(
+        &mut self,
+        input: &[u8],
+        record_builder: &mut ByteRecordBuilder,
+    ) -> (ReadResult, usize) {
+        let mut seps = core::mem::take(&mut self.seps);
+        let (result, pos) =
+            self.split_record_and_find_separators(input, self.scratch.len(), &mut seps);
+
+        match result {
+            ReadResult::InputEmpty => {
+                self.scratch.extend_from_slice(input);
+            }
+            ReadResult::Record => {
+                self.scratch.extend_from_slice(&input[..pos]);
+
+                let slice = self.trim_terminator(&self.scratch);
+                self.delimiter.filter_separators(slice, &mut seps);
+
+                record_builder.set_from_zero_copy(&ZeroCopyByteRecord::new(
+                    slice,
+                    &seps,
+                    self.delimiter,
+                    self.terminator(),
+                    self.quoting(),
+                    self.trim,
+                ));
+
+                self.scratch.clear();
+                seps.clear();
+            }
+            ReadResult::MalformedQuote => {
+                self.scratch.clear();
+                seps.clear();
+            }
+            _ => (),
+        };
+
+        self.seps = seps;
+
+        (result, pos)
+    }
+
     pub(crate) fn read_record(
         &mut self,
         input: &[u8],
@@ -309,14 +904,32 @@ impl CoreReader {
     ) -> (ReadResult, usize) {
         use ReadState::*;
 
+        if self.delimiter.is_multi_byte() || self.trim {
+            return self.read_record_buffered(input, record_builder);
+        }
+
         let input_len = input.len();
 
         if input_len == 0 {
             if !self.record_was_read {
+                if self.is_truncated_after_quote() {
+                    return self.malformed_quote(0);
+                }
+
                 self.record_was_read = true;
 
-                // NOTE: this is required to handle streams not ending with a newline
-                record_builder.finalize_record();
+                if self.is_unterminated() {
+                    return (ReadResult::UnterminatedQuote, 0);
+                }
+
+                // NOTE: this is required to handle streams not ending with a
+                // newline, a stream ending in a quoted field having none
+                if self.ended_in_quote {
+                    record_builder.finalize_field();
+                } else {
+                    self.finalize_record(record_builder);
+                }
+
                 return (ReadResult::Record, 0);
             }
 
@@ -324,11 +937,22 @@ impl CoreReader {
         }
 
         if self.record_was_read {
-            if input[0] == b'\n' {
+            if self.in_comment || self.comment == Some(input[0]) {
+                return self.skip_comment(input);
+            }
+
+            if input[0] == self.newline {
+                if !self.skip_empty_lines {
+                    self.finalize_record(record_builder);
+                    return (ReadResult::Record, 1);
+                }
+
                 return (ReadResult::Lf, 1);
-            } else if input[0] == b'\r' {
+            } else if self.crlf && input[0] == b'\r' {
                 return (ReadResult::Cr, 1);
             }
+
+            self.forget_last_bytes();
         }
 
         self.record_was_read = false;
@@ -339,8 +963,16 @@ impl CoreReader {
             match self.state {
                 Unquoted => {
                     // Fast path for quoted field start
-                    if input[pos] == self.quote {
+                    if self.quoting_enabled
+                        && input[pos] == self.quote
+                        && !self.is_literal_quote(input, pos)
+                    {
+                        if self.strict && !self.is_field_start(input, pos) {
+                            return self.malformed_quote(pos);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                         pos += 1;
                         continue;
                     }
@@ -355,20 +987,29 @@ impl CoreReader {
 
                         // NOTE: we don't copy here yet to avoid slowing down
                         // because of multiple tiny copies.
-                        if byte == self.delimiter {
+                        if byte == self.delimiter.first() {
                             record_builder.finalize_field_preemptively(offset);
                             continue;
                         }
 
-                        if byte == b'\n' {
+                        if byte == self.newline {
                             record_builder.extend_from_slice(&input[pos..pos + offset]);
-                            record_builder.finalize_record();
+                            self.finalize_record(record_builder);
                             self.record_was_read = true;
                             return (ReadResult::Record, pos + last_offset);
                         }
 
                         // Here, `byte` is guaranteed to be a quote
+                        if self.is_literal_quote(input, pos + offset) {
+                            continue;
+                        }
+
+                        if self.strict && !self.is_field_start(input, pos + offset) {
+                            return self.malformed_quote(pos + offset);
+                        }
+
                         self.state = Quoted;
+                        self.entered_quotes = true;
                         record_builder.bump();
                         break;
                     }
@@ -381,45 +1022,92 @@ impl CoreReader {
                     }
                 }
                 Quoted => {
-                    // Here we moving to next quote
-                    if let Some(offset) = memchr(self.quote, &input[pos..]) {
+                    // Here we moving to next quote or escape char
+                    if let Some(offset) = self.find_in_quoted(&input[pos..]) {
                         record_builder.extend_from_slice(&input[pos..pos + offset]);
                         pos += offset + 1;
-                        self.state = Quote;
+
+                        if self.escape == Some(input[pos - 1]) {
+                            self.state = QuotedEscape;
+                        } else {
+                            self.state = Quote;
+                        }
                     } else {
                         break;
                     }
                 }
+                QuotedEscape => {
+                    record_builder.push_byte(input[pos]);
+                    pos += 1;
+                    self.state = Quoted;
+                }
                 Quote => {
                     let byte = input[pos];
 
                     if byte == self.quote {
-                        self.state = Quoted;
+                        if !self.read_quote_after_quote() {
+                            return self.malformed_quote(pos);
+                        }
+
                         record_builder.push_byte(byte);
                         pos += 1;
-                    } else if byte == self.delimiter {
+                    } else if byte == self.delimiter.first() {
                         record_builder.finalize_field();
                         pos += 1;
                         self.state = Unquoted;
-                    } else if byte == b'\n' {
+                    } else if byte == self.newline {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         record_builder.finalize_field();
                         return (ReadResult::Record, pos + 1);
-                    } else if byte == b'\r' && pos + 2 < input_len && input[pos + 2] == b'\n' {
+                    } else if self.crlf && byte == b'\r' && input.get(pos + 1) == Some(&b'\n') {
                         self.record_was_read = true;
                         self.state = Unquoted;
                         record_builder.finalize_field();
                         return (ReadResult::Record, pos + 2);
+                    } else if self.strict {
+                        if !self.check_after_quote(byte) {
+                            return self.malformed_quote(pos);
+                        }
+
+                        // NOTE: here, `byte` is guaranteed to be a CR
+                        record_builder.push_byte(byte);
+                        pos += 1;
                     } else {
                         self.state = Unquoted;
                     }
                 }
+                QuoteDelimiter(_) | QuoteCr => match self.read_after_quote(input[pos]) {
+                    Some(read) => pos += read,
+                    None => return self.malformed_quote(pos),
+                },
+                LiteralTail => {
+                    // Here we are moving to the end of the field
+                    if let Some(offset) =
+                        memchr2(self.delimiter.first(), self.newline, &input[pos..])
+                    {
+                        record_builder.extend_from_slice(&input[pos..pos + offset]);
+                        pos += offset + 1;
+                        self.state = Unquoted;
+
+                        if input[pos - 1] == self.newline {
+                            self.record_was_read = true;
+                            self.finalize_record(record_builder);
+                            return (ReadResult::Record, pos);
+                        }
+
+                        record_builder.finalize_field();
+                    } else {
+                        break;
+                    }
+                }
             }
         }
 
         record_builder.extend_from_slice(&input[pos..]);
 
+        self.remember_last_bytes(input);
+
         (ReadResult::InputEmpty, input_len)
     }
 }
@@ -0,0 +1,664 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt;
+use core::str::{self, FromStr};
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+
+use crate::error::Error;
+use crate::position::Position;
+use crate::records::{ByteRecord, ZeroCopyByteRecord};
+use crate::select::ByteHeadersIndex;
+
+/// Records whose fields can be deserialized, possibly borrowing from `'de`.
+pub(crate) trait DeserializableRecord<'de> {
+    fn len(&self) -> usize;
+    fn field(&self, index: usize) -> Option<Cow<'de, [u8]>>;
+    fn position(&self) -> Option<&Position>;
+}
+
+impl<'de> DeserializableRecord<'de> for &'de ByteRecord {
+    #[inline]
+    fn len(&self) -> usize {
+        ByteRecord::len(self)
+    }
+
+    #[inline]
+    fn field(&self, index: usize) -> Option<Cow<'de, [u8]>> {
+        self.get(index).map(Cow::Borrowed)
+    }
+
+    #[inline]
+    fn position(&self) -> Option<&Position> {
+        ByteRecord::position(self)
+    }
+}
+
+impl<'de> DeserializableRecord<'de> for ZeroCopyByteRecord<'de> {
+    #[inline]
+    fn len(&self) -> usize {
+        ZeroCopyByteRecord::len(self)
+    }
+
+    #[inline]
+    fn field(&self, index: usize) -> Option<Cow<'de, [u8]>> {
+        self.unescape(index)
+    }
+
+    #[inline]
+    fn position(&self) -> Option<&Position> {
+        ZeroCopyByteRecord::position(self)
+    }
+}
+
+#[inline]
+fn custom<T: fmt::Display>(msg: T) -> Error {
+    de::Error::custom(msg)
+}
+
+/// Deserialize the given record, mapping struct fields to columns by name if
+/// `headers` are given, or by position else.
+pub(crate) fn deserialize_record<'de, R, D>(
+    record: &R,
+    headers: Option<&ByteHeadersIndex>,
+) -> Result<D, Error>
+where
+    R: DeserializableRecord<'de>,
+    D: Deserialize<'de>,
+{
+    let mut deserializer = RecordDeserializer {
+        record,
+        headers,
+        current: 0,
+    };
+
+    D::deserialize(&mut deserializer).map_err(|err| err.at_position(record.position()))
+}
+
+struct RecordDeserializer<'r, 'h, R> {
+    record: &'r R,
+    headers: Option<&'h ByteHeadersIndex>,
+    // NOTE: index of the next field to yield when deserializing a sequence
+    current: usize,
+}
+
+impl<'de, R: DeserializableRecord<'de>> RecordDeserializer<'_, '_, R> {
+    fn field(&self, index: usize) -> Result<FieldDeserializer<'static, 'de>, Error> {
+        self.record
+            .field(index)
+            .map(FieldDeserializer::from)
+            .ok_or_else(|| custom("missing field"))
+    }
+
+    fn headers(&self) -> Result<&ByteHeadersIndex, Error> {
+        self.headers
+            .ok_or_else(|| custom("cannot deserialize a map without headers"))
+    }
+}
+
+// NOTE: scalars are read from the first field of the record, so that records
+// with a single column can be deserialized directly into e.g. an integer.
+macro_rules! forward_to_first_field {
+    ($($method:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.field(0)?.$method(visitor).map_err(|err| err.at_field(0))
+            }
+        )*
+    };
+}
+
+impl<'de, R: DeserializableRecord<'de>> Deserializer<'de> for &mut RecordDeserializer<'_, '_, R> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.headers.is_some() {
+            self.deserialize_map(visitor)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    forward_to_first_field!(
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_identifier
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let headers = self.headers()?;
+
+        visitor.visit_map(HeadersAccess {
+            record: self.record,
+            headers,
+            current: 0,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.headers {
+            None => visitor.visit_seq(self),
+            Some(headers) => visitor.visit_map(StructAccess {
+                record: self.record,
+                headers,
+                fields: fields.iter(),
+                column: 0,
+            }),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.field(0)?
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|err| err.at_field(0))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de, R: DeserializableRecord<'de>> SeqAccess<'de> for &mut RecordDeserializer<'_, '_, R> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let index = self.current;
+
+        match self.record.field(index) {
+            None => Ok(None),
+            Some(cell) => {
+                self.current += 1;
+
+                seed.deserialize(FieldDeserializer::from(cell))
+                    .map(Some)
+                    .map_err(|err| err.at_field(index))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.record.len() - self.current)
+    }
+}
+
+/// Yields struct fields found in the headers, in the order they were declared.
+struct StructAccess<'r, 'h, R> {
+    record: &'r R,
+    headers: &'h ByteHeadersIndex,
+    fields: core::slice::Iter<'static, &'static str>,
+    column: usize,
+}
+
+impl<'de, R: DeserializableRecord<'de>> MapAccess<'de> for StructAccess<'_, '_, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        // NOTE: fields absent from the headers are left to serde, which will
+        // either complain or use a default, e.g. `None` for options
+        for name in self.fields.by_ref() {
+            let column = match self.headers.first_column_index_by_name(name) {
+                Some(column) if column < self.record.len() => column,
+                _ => continue,
+            };
+
+            self.column = column;
+
+            return seed
+                .deserialize(BorrowedStrDeserializer::<Error>::new(name))
+                .map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let cell = self.record.field(self.column).unwrap();
+
+        seed.deserialize(FieldDeserializer::from(cell))
+            .map_err(|err| err.at_field(self.column))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+/// Yields every header along with the corresponding field.
+struct HeadersAccess<'r, 'h, R> {
+    record: &'r R,
+    headers: &'h ByteHeadersIndex,
+    current: usize,
+}
+
+impl<'de, R: DeserializableRecord<'de>> MapAccess<'de> for HeadersAccess<'_, '_, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.current >= self.headers.len().min(self.record.len()) {
+            return Ok(None);
+        }
+
+        seed.deserialize(FieldDeserializer::Transient(&self.headers[self.current]))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let index = self.current;
+        let cell = self.record.field(index).unwrap();
+
+        self.current += 1;
+
+        seed.deserialize(FieldDeserializer::from(cell))
+            .map_err(|err| err.at_field(index))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.headers.len().min(self.record.len()) - self.current)
+    }
+}
+
+/// Deserializes a single field, which can only be a scalar value.
+enum FieldDeserializer<'a, 'de> {
+    Borrowed(&'de [u8]),
+    Transient(&'a [u8]),
+    Owned(Vec<u8>),
+}
+
+impl<'de> From<Cow<'de, [u8]>> for FieldDeserializer<'_, 'de> {
+    fn from(cell: Cow<'de, [u8]>) -> Self {
+        match cell {
+            Cow::Borrowed(bytes) => Self::Borrowed(bytes),
+            Cow::Owned(bytes) => Self::Owned(bytes),
+        }
+    }
+}
+
+impl FieldDeserializer<'_, '_> {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(bytes) => bytes,
+            Self::Transient(bytes) => bytes,
+            Self::Owned(bytes) => bytes,
+        }
+    }
+
+    fn as_str(&self) -> Result<&str, Error> {
+        str::from_utf8(self.as_bytes()).map_err(custom)
+    }
+
+    fn parse<T: FromStr>(&self) -> Result<T, Error> {
+        let string = self.as_str()?;
+
+        string.parse().map_err(|_| {
+            custom(format_args!(
+                "cannot parse {:?} as {}",
+                string,
+                type_name::<T>()
+            ))
+        })
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for FieldDeserializer<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if str::from_utf8(self.as_bytes()).is_ok() {
+            self.deserialize_str(visitor)
+        } else {
+            self.deserialize_bytes(visitor)
+        }
+    }
+
+    deserialize_parsed!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char
+    );
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Self::Borrowed(bytes) => {
+                visitor.visit_borrowed_str(str::from_utf8(bytes).map_err(custom)?)
+            }
+            Self::Transient(bytes) => visitor.visit_str(str::from_utf8(bytes).map_err(custom)?),
+            Self::Owned(bytes) => visitor.visit_string(String::from_utf8(bytes).map_err(custom)?),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Self::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Self::Transient(bytes) => visitor.visit_bytes(bytes),
+            Self::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.as_bytes().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.as_str()?.into_deserializer())
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    // NOTE: nested structures cannot be represented by a single field, so
+    // serde will report an invalid type
+    serde::forward_to_deserialize_any! {
+        seq tuple tuple_struct map struct
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::error::{self, ErrorKind};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Person {
+        name: String,
+        age: u8,
+        alive: bool,
+        score: Option<f64>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Color {
+        Red,
+        Blue,
+    }
+
+    #[test]
+    fn test_deserialize_byte_record() -> error::Result<()> {
+        let headers = brec!["score", "alive", "name", "age", "extra"];
+        let record = brec!["4.5", "true", "john", "45", "whatever"];
+
+        assert_eq!(
+            record.deserialize::<Person>(Some(&headers))?,
+            Person {
+                name: "john".to_string(),
+                age: 45,
+                alive: true,
+                score: Some(4.5)
+            }
+        );
+
+        let record = brec!["", "false", "lucy", "67", ""];
+
+        assert_eq!(record.deserialize::<Person>(Some(&headers))?.score, None);
+
+        // Missing optional columns
+        let headers = brec!["name", "age", "alive"];
+        let record = brec!["lucy", "67", "false"];
+
+        assert_eq!(record.deserialize::<Person>(Some(&headers))?.score, None);
+
+        // By position
+        let record = brec!["john", "45", "true", "3"];
+
+        assert_eq!(
+            record.deserialize::<Person>(None)?,
+            Person {
+                name: "john".to_string(),
+                age: 45,
+                alive: true,
+                score: Some(3.0)
+            }
+        );
+        assert_eq!(
+            record.deserialize::<(&str, u8, bool, Option<i32>)>(None)?,
+            ("john", 45, true, Some(3))
+        );
+        assert_eq!(
+            record.deserialize::<Vec<&[u8]>>(None)?,
+            vec![&b"john"[..], b"45", b"true", b"3"]
+        );
+        assert_eq!(brec!["blue"].deserialize::<Color>(None)?, Color::Blue);
+        assert_eq!(brec!["red"].deserialize::<Color>(None)?, Color::Red);
+        assert_eq!(brec!["17"].deserialize::<u64>(None)?, 17);
+
+        // Maps
+        let headers = brec!["name", "surname"];
+
+        assert_eq!(
+            brec!["john", "landis"].deserialize::<BTreeMap<String, &str>>(Some(&headers))?,
+            BTreeMap::from([
+                ("name".to_string(), "john"),
+                ("surname".to_string(), "landis")
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_zero_copy_byte_record() -> error::Result<()> {
+        #[derive(Deserialize)]
+        struct Row<'a> {
+            name: &'a str,
+            #[serde(borrow)]
+            notes: Cow<'a, str>,
+            count: Option<usize>,
+        }
+
+        let headers = brec!["name", "notes", "count"];
+        let data = b"\"john\",\"the \"\"king\"\"\",";
+        let seps = [6, 21];
+
+        let record = ZeroCopyByteRecord::new(
+            data,
+            &seps,
+            crate::dialect::Delimiter::from(b','),
+            crate::dialect::Terminator::CRLF,
+            crate::dialect::Quoting::default(),
+            false,
+        );
+
+        let row = record.deserialize::<Row>(Some(&headers))?;
+
+        assert_eq!(row.name, "john");
+        assert!(matches!(row.notes, Cow::Owned(_)));
+        assert_eq!(row.notes, "the \"king\"");
+        assert_eq!(row.count, None);
+
+        assert!(record.deserialize::<(&str, &str)>(None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        let headers = brec!["name", "age", "alive"];
+        let mut record = brec!["john", "old", "true"];
+        record.set_position(Some(Position {
+            byte: 13,
+            line: 2,
+            record: 1,
+        }));
+
+        let err = record.deserialize::<Person>(Some(&headers)).unwrap_err();
+
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::Deserialize {
+                pos: Some((13, 1)),
+                field: Some(1),
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "CSV deserialize error: record 1 (byte: 13): field 1: cannot parse \"old\" as u8"
+        );
+
+        let err = brec!["john"]
+            .deserialize::<Person>(Some(&headers))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "CSV deserialize error: missing field `age`"
+        );
+
+        assert!(brec!["john"]
+            .deserialize::<BTreeMap<String, String>>(None)
+            .is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use alloc::vec::Vec;
+
+use memchr::{memchr, memchr2};
+
+use crate::preamble::SkipLines;
+use crate::utils::unquoted;
+
+/// CSV dialect options shared by the builders and forwarded to the inner
+/// [`CoreReader`](crate::core::CoreReader).
+#[derive(Debug, Clone)]
+pub(crate) struct Dialect {
+    pub(crate) delimiter: Delimiter,
+    pub(crate) quote: u8,
+    pub(crate) quoting: bool,
+    pub(crate) escape: Option<u8>,
+    pub(crate) double_quote: bool,
+    pub(crate) comment: Option<u8>,
+    pub(crate) terminator: Terminator,
+    pub(crate) strict: bool,
+    pub(crate) lenient_quotes: bool,
+    pub(crate) skip_empty_lines: bool,
+    pub(crate) skip_lines: SkipLines,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::from(b','),
+            quote: b'"',
+            quoting: true,
+            escape: None,
+            double_quote: true,
+            comment: None,
+            terminator: Terminator::CRLF,
+            strict: false,
+            lenient_quotes: false,
+            skip_empty_lines: true,
+            skip_lines: SkipLines::None,
+        }
+    }
+}
+
+/// The record terminator to use when reading or writing CSV data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Terminator {
+    /// When reading, records will be terminated by either LF or CRLF, and
+    /// leading CR characters will be trimmed from records. When writing, CRLF
+    /// will be used.
+    #[default]
+    CRLF,
+    /// Records will be terminated by the given byte, e.g. `b'\r'` for
+    /// legacy Mac files or `b'\x1e'` for the ASCII record separator. No
+    /// special care will be taken regarding CR characters.
+    Any(u8),
+}
+
+impl Terminator {
+    #[inline(always)]
+    pub(crate) fn byte(&self) -> u8 {
+        match self {
+            Self::CRLF => b'\n',
+            Self::Any(byte) => *byte,
+        }
+    }
+}
+
+/// Whether fields should be trimmed of their surrounding ASCII whitespace
+/// when reading CSV data.
+///
+/// Quoted fields are only trimmed outside of their quotes, so that
+/// `" hello" ` will become ` hello`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Trim {
+    /// Nothing will be trimmed.
+    #[default]
+    None,
+    /// Only headers will be trimmed.
+    Headers,
+    /// Only fields of non-header records will be trimmed.
+    Fields,
+    /// Both headers and fields will be trimmed.
+    All,
+}
+
+impl Trim {
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn headers(&self) -> bool {
+        matches!(self, Self::Headers | Self::All)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn fields(&self) -> bool {
+        matches!(self, Self::Fields | Self::All)
+    }
+}
+
+/// A field delimiter spanning one or several bytes.
+///
+/// Only the first byte is fed to the SIMD searcher, the remaining ones being
+/// verified when a candidate is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Delimiter {
+    bytes: [u8; Self::MAX_LEN],
+    len: usize,
+}
+
+impl Delimiter {
+    pub(crate) const MAX_LEN: usize = 4;
+
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        assert!(
+            !bytes.is_empty() && bytes.len() <= Self::MAX_LEN,
+            "delimiter must be between 1 and {} bytes long",
+            Self::MAX_LEN
+        );
+
+        let mut delimiter = Self {
+            bytes: [0; Self::MAX_LEN],
+            len: bytes.len(),
+        };
+        delimiter.bytes[..bytes.len()].copy_from_slice(bytes);
+        delimiter
+    }
+
+    #[inline(always)]
+    pub(crate) fn first(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    #[inline(always)]
+    pub(crate) fn last(&self) -> u8 {
+        self.bytes[self.len - 1]
+    }
+
+    #[inline(always)]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_multi_byte(&self) -> bool {
+        self.len > 1
+    }
+
+    #[inline(always)]
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Delimiter to use when reading the data backwards.
+    #[cfg(feature = "std")]
+    pub(crate) fn reversed(&self) -> Self {
+        let mut delimiter = *self;
+        delimiter.bytes[..self.len].reverse();
+        delimiter
+    }
+
+    /// Only keep the separator candidates, found using the first byte, that
+    /// are actually followed by the rest of the delimiter, without
+    /// overlapping. Positions are relative to the given record slice.
+    pub(crate) fn filter_separators(&self, slice: &[u8], seps: &mut Vec<usize>) {
+        if !self.is_multi_byte() {
+            return;
+        }
+
+        let delimiter = self.as_bytes();
+        let mut next_allowed = 0;
+
+        seps.retain(|&i| {
+            if i >= next_allowed && slice.get(i..i + self.len) == Some(delimiter) {
+                next_allowed = i + self.len;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+impl From<u8> for Delimiter {
+    fn from(byte: u8) -> Self {
+        Self::new(&[byte])
+    }
+}
+
+/// Everything required to know how to unescape a quoted cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Quoting {
+    pub(crate) enabled: bool,
+    pub(crate) quote: u8,
+    pub(crate) escape: Option<u8>,
+    pub(crate) double_quote: bool,
+    // NOTE: only a quote at the beginning of a cell starts a quoted field
+    pub(crate) lenient: bool,
+}
+
+impl Quoting {
+    /// Returns the given cell without its surrounding quotes, if it is quoted
+    /// and quoting is enabled.
+    #[inline(always)]
+    pub(crate) fn unquoted<'c>(&self, cell: &'c [u8]) -> Option<&'c [u8]> {
+        if !self.enabled {
+            return None;
+        }
+
+        unquoted(cell, self.quote)
+    }
+
+    /// Returns the quoted part of the given cell, without its quotes, along
+    /// with the bytes following its closing quote, if it is quoted and quoting
+    /// is enabled.
+    #[inline]
+    pub(crate) fn split_quoted<'c>(&self, cell: &'c [u8]) -> Option<(&'c [u8], &'c [u8])> {
+        if !self.enabled || cell.first() != Some(&self.quote) {
+            return None;
+        }
+
+        if !self.lenient && self.double_quote {
+            if let Some(inner) = unquoted(cell, self.quote) {
+                return Some((inner, &cell[..0]));
+            }
+        }
+
+        // NOTE: what follows the closing quote is taken literally, as would the
+        // core reader do in lenient mode or when quotes are not doubled, in
+        // which case the first quote that is not escaped closes the quoted
+        // part, and a cell whose quote was never closed, e.g. when truncated,
+        // is only stripped of its opening quote
+        let len = cell.len();
+        let mut i = 1;
+
+        while i < len {
+            let offset = match self.escape {
+                Some(escape) => memchr2(self.quote, escape, &cell[i..]),
+                None => memchr(self.quote, &cell[i..]),
+            };
+
+            let Some(offset) = offset else {
+                break;
+            };
+
+            let j = i + offset;
+
+            if Some(cell[j]) == self.escape
+                || (self.double_quote && cell.get(j + 1) == Some(&self.quote))
+            {
+                i = j + 2;
+                continue;
+            }
+
+            return Some((&cell[1..j], &cell[j + 1..]));
+        }
+
+        Some((&cell[1..], &cell[len..]))
+    }
+}
+
+impl Default for Quoting {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quote: b'"',
+            escape: None,
+            double_quote: true,
+            lenient: false,
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use std::io::{self, Read};
+
+const CHUNK_SIZE: usize = 8192;
+const REPLACEMENT_CHARACTER: char = '\u{FFFD}';
+
+// NOTE: code points of the 0x80..=0x9F range of Windows-1252, undefined bytes
+// being mapped to their C1 control counterpart like the WHATWG spec does.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// A character encoding that can be transcoded to UTF-8 by a
+/// [`DecodingReader`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8, bytes will be yielded as-is.
+    #[default]
+    Utf8,
+    /// Little-endian UTF-16, as typically produced by Excel.
+    Utf16Le,
+    /// Big-endian UTF-16.
+    Utf16Be,
+    /// ISO-8859-1, where each byte maps to the code point of same value.
+    Latin1,
+    /// Windows-1252, the legacy superset of Latin-1 used by Windows.
+    Windows1252,
+}
+
+#[inline(always)]
+fn push_char(c: char, output: &mut Vec<u8>) {
+    let mut buf = [0u8; 4];
+    output.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+}
+
+fn decode_single_byte(input: &[u8], table: Option<&[char; 32]>, output: &mut Vec<u8>) {
+    for byte in input.iter().copied() {
+        if byte < 0x80 {
+            output.push(byte);
+            continue;
+        }
+
+        let c = match table {
+            Some(table) if byte < 0xA0 => table[(byte - 0x80) as usize],
+            _ => char::from(byte),
+        };
+
+        push_char(c, output);
+    }
+}
+
+// Returns the number of bytes that could be decoded, the remaining ones being
+// an incomplete code unit or surrogate pair.
+fn decode_utf16(input: &[u8], big_endian: bool, eof: bool, output: &mut Vec<u8>) -> usize {
+    let unit_at = |i: usize| -> u32 {
+        let bytes = [input[i], input[i + 1]];
+
+        (if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }) as u32
+    };
+
+    let mut i: usize = 0;
+
+    while i + 2 <= input.len() {
+        let unit = unit_at(i);
+
+        match unit {
+            0xD800..=0xDBFF => {
+                if i + 4 > input.len() {
+                    if !eof {
+                        break;
+                    }
+
+                    push_char(REPLACEMENT_CHARACTER, output);
+                    i += 2;
+                    continue;
+                }
+
+                let next = unit_at(i + 2);
+
+                if (0xDC00..=0xDFFF).contains(&next) {
+                    let code_point = 0x10000 + ((unit - 0xD800) << 10) + (next - 0xDC00);
+                    push_char(
+                        char::from_u32(code_point).unwrap_or(REPLACEMENT_CHARACTER),
+                        output,
+                    );
+                    i += 4;
+                } else {
+                    push_char(REPLACEMENT_CHARACTER, output);
+                    i += 2;
+                }
+            }
+            0xDC00..=0xDFFF => {
+                push_char(REPLACEMENT_CHARACTER, output);
+                i += 2;
+            }
+            _ => {
+                push_char(
+                    char::from_u32(unit).unwrap_or(REPLACEMENT_CHARACTER),
+                    output,
+                );
+                i += 2;
+            }
+        }
+    }
+
+    if eof && i < input.len() {
+        push_char(REPLACEMENT_CHARACTER, output);
+        i = input.len();
+    }
+
+    i
+}
+
+/// A [`Read`] adapter transcoding its input to UTF-8 on the fly, so that it
+/// can be fed to any of this crate's readers.
+///
+/// A leading UTF-8 or UTF-16 BOM will always take precedence over the given
+/// encoding, and will be yielded as a UTF-8 BOM so that readers can report it
+/// through their `input_had_bom` method.
+///
+/// Invalid or truncated UTF-16 sequences will be replaced by `U+FFFD`.
+pub struct DecodingReader<R> {
+    inner: R,
+    encoding: Encoding,
+    has_sniffed: bool,
+    raw: Vec<u8>,
+    raw_len: usize,
+    decoded: Vec<u8>,
+    decoded_pos: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Create a new decoding reader using the provided reader implementing
+    /// [`std::io::Read`] and the encoding to use when the input does not start
+    /// with a BOM.
+    pub fn new(inner: R, encoding: Encoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            has_sniffed: false,
+            raw: Vec::new(),
+            raw_len: 0,
+            decoded: Vec::new(),
+            decoded_pos: 0,
+        }
+    }
+
+    /// Returns the encoding being transcoded, which might differ from the
+    /// given one if a BOM was found after the first read.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Unwrap the underlying reader. Buffered bytes will be lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn sniff(&mut self) -> io::Result<()> {
+        self.raw.resize(CHUNK_SIZE, 0);
+
+        while self.raw_len < 3 {
+            let n = self.inner.read(&mut self.raw[self.raw_len..])?;
+
+            if n == 0 {
+                break;
+            }
+
+            self.raw_len += n;
+        }
+
+        match &self.raw[..self.raw_len] {
+            [0xEF, 0xBB, 0xBF, ..] => self.encoding = Encoding::Utf8,
+            [0xFF, 0xFE, ..] => self.encoding = Encoding::Utf16Le,
+            [0xFE, 0xFF, ..] => self.encoding = Encoding::Utf16Be,
+            _ => (),
+        };
+
+        self.has_sniffed = true;
+
+        Ok(())
+    }
+
+    // Returns whether the input was exhausted
+    fn decode_next_chunk(&mut self) -> io::Result<bool> {
+        loop {
+            let n = self.inner.read(&mut self.raw[self.raw_len..])?;
+            let eof = n == 0;
+
+            self.raw_len += n;
+
+            let input = &self.raw[..self.raw_len];
+
+            self.decoded.clear();
+            self.decoded_pos = 0;
+
+            let consumed = match self.encoding {
+                Encoding::Utf8 => {
+                    self.decoded.extend_from_slice(input);
+                    input.len()
+                }
+                Encoding::Latin1 => {
+                    decode_single_byte(input, None, &mut self.decoded);
+                    input.len()
+                }
+                Encoding::Windows1252 => {
+                    decode_single_byte(input, Some(&WINDOWS_1252_HIGH), &mut self.decoded);
+                    input.len()
+                }
+                Encoding::Utf16Le => decode_utf16(input, false, eof, &mut self.decoded),
+                Encoding::Utf16Be => decode_utf16(input, true, eof, &mut self.decoded),
+            };
+
+            self.raw.copy_within(consumed..self.raw_len, 0);
+            self.raw_len -= consumed;
+
+            if eof || !self.decoded.is_empty() {
+                return Ok(eof && self.decoded.is_empty());
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.has_sniffed {
+            self.sniff()?;
+        }
+
+        if self.decoded_pos >= self.decoded.len() {
+            // NOTE: UTF-8 input can be read directly once sniffed bytes are
+            // exhausted
+            if self.encoding == Encoding::Utf8 && self.raw_len == 0 {
+                return self.inner.read(buf);
+            }
+
+            if self.decode_next_chunk()? {
+                return Ok(0);
+            }
+        }
+
+        let available = &self.decoded[self.decoded_pos..];
+        let n = available.len().min(buf.len());
+
+        buf[..n].copy_from_slice(&available[..n]);
+        self.decoded_pos += n;
+
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8], encoding: Encoding) -> String {
+        let mut output = String::new();
+
+        DecodingReader::new(bytes, encoding)
+            .read_to_string(&mut output)
+            .unwrap();
+
+        output
+    }
+
+    fn utf16(string: &str, big_endian: bool) -> Vec<u8> {
+        string
+            .encode_utf16()
+            .flat_map(|unit| {
+                if big_endian {
+                    unit.to_be_bytes()
+                } else {
+                    unit.to_le_bytes()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_single_byte() {
+        assert_eq!(decode(b"caf\xe9,na\xefve", Encoding::Latin1), "café,naïve");
+        assert_eq!(decode(b"\x80 \x93ok\x94", Encoding::Windows1252), "€ “ok”");
+        assert_eq!(decode(b"\x80", Encoding::Latin1), "\u{80}");
+    }
+
+    #[test]
+    fn test_utf16() {
+        let string = "\u{FEFF}name,emoji\néloïse,🦀\n";
+
+        assert_eq!(decode(&utf16(string, false), Encoding::Utf8), string);
+        assert_eq!(decode(&utf16(string, true), Encoding::Latin1), string);
+        assert_eq!(decode(&utf16("a,🦀", false)[2..], Encoding::Utf16Le), ",🦀");
+
+        // Lone surrogates and truncated input
+        let mut bytes = utf16("a🦀", false);
+        bytes.truncate(4);
+        bytes.push(b'b');
+
+        assert_eq!(decode(&bytes, Encoding::Utf16Le), "a\u{FFFD}\u{FFFD}");
+    }
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let string = "é🦀".repeat(CHUNK_SIZE / 4);
+        let bytes = utf16(&string, false);
+
+        // NOTE: reading one byte at a time to stress incomplete sequences
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(1);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let mut reader = DecodingReader::new(Trickle(&bytes), Encoding::Utf16Le);
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+
+        assert_eq!(output, string);
+    }
+
+    #[test]
+    fn test_utf8_bom() {
+        let mut reader = DecodingReader::new(&b"\xef\xbb\xbfcaf\xc3\xa9"[..], Encoding::Latin1);
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output).unwrap();
+
+        assert_eq!(reader.encoding(), Encoding::Utf8);
+        assert_eq!(output, "\u{FEFF}café".as_bytes());
+    }
+}
@@ -1,10 +1,22 @@
-use std::{error, fmt, io, result};
+use alloc::string::String;
+use core::{error, fmt, result};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "serde")]
+use crate::position::Position;
 
 /// The specific type of an error.
+///
+/// Positions are given as `(byte_offset, record_index)`, where the record
+/// index is the same as [`Position::record`](crate::Position::record), i.e.
+/// starting at 0 and counting the header row, if any.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum ErrorKind {
     /// Wrap a [std::io::Error].
+    #[cfg(feature = "std")]
     Io(io::Error),
 
     /// Indicate that utf-8 decoding failed when reading a record.
@@ -27,6 +39,69 @@ pub enum ErrorKind {
         pos: Option<(u64, u64)>,
     },
 
+    /// Indicate that a reader configured to be strict found a quote in the
+    /// middle of an unquoted field, or unexpected data after a closing quote.
+    MalformedQuote {
+        /// Optional position `(byte_offset, record_index)` of the offending byte
+        pos: Option<(u64, u64)>,
+    },
+
+    /// Indicate that a reader configured to be strict reached the end of the
+    /// stream in the middle of a quoted field, e.g. because the stream was
+    /// truncated.
+    UnterminatedQuote {
+        /// Optional position `(byte_offset, record_index)` of the unterminated
+        /// record
+        pos: Option<(u64, u64)>,
+    },
+
+    /// Indicate that a record spans more bytes than the maximum record size
+    /// a reader was configured with.
+    RecordTooLarge {
+        /// Optional position `(byte_offset, record_index)` of the record
+        pos: Option<(u64, u64)>,
+        /// Maximum record size, in bytes
+        limit: usize,
+    },
+
+    /// Indicate that a record has more fields than the maximum number of
+    /// fields a reader was configured with.
+    TooManyFields {
+        /// Optional position `(byte_offset, record_index)` of the record
+        pos: Option<(u64, u64)>,
+        /// Maximum number of fields
+        limit: usize,
+    },
+
+    /// Indicate that a writer configured without quoting was given a cell
+    /// containing the delimiter or the record terminator, or a record made of
+    /// a single empty cell, which cannot be written without corrupting the CSV
+    /// data.
+    UnquotableCell,
+
+    /// Indicate that bytes given to [`Checkpoint::from_bytes`](crate::Checkpoint::from_bytes)
+    /// do not represent a valid checkpoint.
+    #[cfg(feature = "std")]
+    InvalidCheckpoint,
+
+    /// Indicate that a record could not be deserialized into the requested
+    /// type.
+    #[cfg(feature = "serde")]
+    Deserialize {
+        /// Optional position `(byte_offset, record_index)` of the record
+        pos: Option<(u64, u64)>,
+        /// Optional index of the offending field
+        field: Option<usize>,
+        /// Description of the problem
+        message: String,
+    },
+
+    /// Indicate that a value could not be serialized as a CSV record, e.g.
+    /// because it contains nested structures that cannot fit in a single
+    /// field.
+    #[cfg(feature = "serde")]
+    Serialize(String),
+
     /// Indicate that a [`Seeker`](crate::Seeker) attempted to find a record in
     /// a position that is out of bounds
     OutOfBounds {
@@ -49,6 +124,7 @@ impl Error {
     }
 
     /// Return whether the wrapped error is a [`std::io::Error`].
+    #[cfg(feature = "std")]
     pub fn is_io_error(&self) -> bool {
         matches!(self.0, ErrorKind::Io(_))
     }
@@ -64,12 +140,14 @@ impl Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
         Self(ErrorKind::Io(err))
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Error> for io::Error {
     fn from(err: Error) -> Self {
         Self::other(err)
@@ -81,6 +159,7 @@ impl error::Error for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.0 {
+            #[cfg(feature = "std")]
             ErrorKind::Io(ref err) => err.fmt(f),
             ErrorKind::Utf8Error => write!(f, "utf8 decode error"),
             ErrorKind::SelectorParseError(ref msg) => write!(f, "{}", msg),
@@ -103,6 +182,72 @@ impl fmt::Display for Error {
                 "CSV error: found record with {} fields, but the previous record has {} fields",
                 len, expected_len
             ),
+            ErrorKind::MalformedQuote {
+                pos: Some((byte, index)),
+            } => write!(
+                f,
+                "CSV error: record {} (byte: {}): malformed quote",
+                index, byte
+            ),
+            ErrorKind::MalformedQuote { pos: None } => write!(f, "CSV error: malformed quote"),
+            ErrorKind::UnterminatedQuote {
+                pos: Some((byte, index)),
+            } => write!(
+                f,
+                "CSV error: record {} (byte: {}): unterminated quoted field at end of stream",
+                index, byte
+            ),
+            ErrorKind::UnterminatedQuote { pos: None } => {
+                write!(f, "CSV error: unterminated quoted field at end of stream")
+            }
+            ErrorKind::RecordTooLarge {
+                pos: Some((byte, index)),
+                limit,
+            } => write!(
+                f,
+                "CSV error: record {} (byte: {}): record is larger than {} bytes",
+                index, byte, limit
+            ),
+            ErrorKind::RecordTooLarge { pos: None, limit } => {
+                write!(f, "CSV error: record is larger than {} bytes", limit)
+            }
+            ErrorKind::TooManyFields {
+                pos: Some((byte, index)),
+                limit,
+            } => write!(
+                f,
+                "CSV error: record {} (byte: {}): record has more than {} fields",
+                index, byte, limit
+            ),
+            ErrorKind::TooManyFields { pos: None, limit } => {
+                write!(f, "CSV error: record has more than {} fields", limit)
+            }
+            ErrorKind::UnquotableCell => write!(
+                f,
+                "CSV error: record cannot be written without quoting"
+            ),
+            #[cfg(feature = "std")]
+            ErrorKind::InvalidCheckpoint => write!(f, "invalid checkpoint data"),
+            #[cfg(feature = "serde")]
+            ErrorKind::Deserialize {
+                pos,
+                field,
+                ref message,
+            } => {
+                write!(f, "CSV deserialize error: ")?;
+
+                if let Some((byte, index)) = pos {
+                    write!(f, "record {} (byte: {}): ", index, byte)?;
+                }
+
+                if let Some(field) = field {
+                    write!(f, "field {}: ", field)?;
+                }
+
+                write!(f, "{}", message)
+            }
+            #[cfg(feature = "serde")]
+            ErrorKind::Serialize(ref msg) => write!(f, "CSV serialize error: {}", msg),
             ErrorKind::OutOfBounds { pos, start, end } => {
                 write!(f, "pos {} is out of bounds (should be >= {} and < {})", pos, start, end)
             }
@@ -110,5 +255,48 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        use alloc::string::ToString;
+
+        Self(ErrorKind::Deserialize {
+            pos: None,
+            field: None,
+            message: msg.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        use alloc::string::ToString;
+
+        Self(ErrorKind::Serialize(msg.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error {
+    pub(crate) fn at_field(mut self, index: usize) -> Self {
+        if let ErrorKind::Deserialize { ref mut field, .. } = self.0 {
+            field.get_or_insert(index);
+        }
+
+        self
+    }
+
+    pub(crate) fn at_position(mut self, position: Option<&Position>) -> Self {
+        if let ErrorKind::Deserialize { ref mut pos, .. } = self.0 {
+            if pos.is_none() {
+                *pos = position.map(|p| (p.byte, p.record));
+            }
+        }
+
+        self
+    }
+}
+
 /// A type alias for `Result<T, simd_csv::Error>`.
 pub type Result<T> = result::Result<T, Error>;
@@ -88,10 +88,30 @@ You can also find more exotic readers like:
   This can be very useful for parallelization, or more creative uses like performing binary
   search in a sorted file.
 - [`ReverseReader`], [`ReaderBuilder`]: a reader able to read a seekable CSV stream in reverse, in amortized linear time.
+- [`ParallelReader`], [`ParallelReaderBuilder`]: a reader splitting a seekable CSV stream into segments
+  using a [`Seeker`], to parse them on multiple threads (or a [`rayon`](https://docs.rs/rayon/) pool, through the `rayon` feature).
+- [`PushParser`], [`PushParserBuilder`]: a push-based parser, fed with chunks of data instead of
+  reading a stream, for when data arrives through callbacks.
+- `AsyncReader`, `AsyncZeroCopyReader`, `AsyncSplitter`: async counterparts of the streaming
+  readers working on [`tokio`](https://docs.rs/tokio/) `AsyncRead` streams, available through the `tokio` feature.
 
 # Writers
 
 - [`Writer`], [`WriterBuilder`]: a typical CSV writer.
+- `AsyncWriter`: async counterpart of the writer working on [`tokio`](https://docs.rs/tokio/)
+  `AsyncWrite` streams, available through the `tokio` feature.
+
+# Serde
+
+Records can be deserialized into any type implementing `serde::Deserialize`
+through the `serde` feature, using `Reader::deserialize` or
+`ByteRecord::deserialize`. Struct fields are mapped to columns by header name,
+and `&str` or `&[u8]` fields can even be borrowed from a `ZeroCopyByteRecord`
+when they do not need unescaping.
+
+Conversely, any type implementing `serde::Serialize` can be written using
+`Writer::serialize`, which will write a header row made of struct field names
+on first call.
 
 # Supported targets
 
@@ -107,6 +127,12 @@ this crate because it either uses SIMD instructions tied to your `target_arch`
 already and because it will rely on runtime detection to find better SIMD
 instructions (typically `avx2`).
 
+The crate can also be used in `no_std` environments providing an allocator, by
+disabling its default `std` feature. Only the [`TotalReader`], the [`PushParser`]
+and the records remain available in this case, since every other reader or writer
+relies on `std::io`. Note that SIMD instructions cannot be detected at runtime
+without the `std` feature.
+
 # Design notes
 
 ## Regarding performance
@@ -283,11 +309,29 @@ the parser's state machine is not geared towards this at all.
 Rest assured that morally valid & sensical CSV data will still be parsed
 correctly ;)
 
+If you would rather fail loudly than silently produce misaligned records, the
+readers can be configured using [`ReaderBuilder::strict`] so that they return an
+[`ErrorKind::MalformedQuote`] error when encountering such data instead. Reading
+can go on after such an error, the rest of the offending line being skipped. Strict
+readers will also return an [`ErrorKind::UnterminatedQuote`] error when the stream
+ends in the middle of a quoted field, typically because it was truncated. Other
+readers will yield the last record as is, but [`Reader::ended_in_quote`] can be checked
+afterwards to detect this case.
+
+Finally, if you need to read such data the same way the `csv` crate would, the
+readers can be configured using [`ReaderBuilder::lenient_quotes`] so that only quotes found at the
+very beginning of a field will open a quoted field.
+
 ## Regarding line terminators
 
 To avoid needless branching and SIMD overhead, this crate's CSV parser
 expect line terminators to be either CRLF or single LF, but not single CR.
 
+If you need to read files using single CR (e.g. legacy Mac exports), or any
+other single byte, as line terminator, you can use [`Terminator::Any`] when
+configuring your reader instead. In which case the following caveat does not
+apply and no special care will be taken regarding CR characters.
+
 Also, to avoid state machine overhead related to CRLF at buffer boundaries
 when streaming and to make sure we skip empty lines of the file (we
 don't parse them as empty records),
@@ -373,7 +417,25 @@ This choice is usually made by CSV parsers for the following reasons:
    expecting a consistent number of columns across files, when they suddenly encounter
    a row without any column (or a single one, depending on your philosophy).
 
+That being said, if you need to yield empty lines as records having a single
+empty field, e.g. to count physical rows, you can configure your reader using
+[`ReaderBuilder::skip_empty_lines`].
+
+## Regarding encodings
+
+This crate's readers work on bytes and only expect ASCII-compatible data,
+trimming a leading UTF-8 BOM if any (which can be checked afterwards using
+[`Reader::input_had_bom`]).
+
+UTF-16 data, as typically exported by Excel, or legacy single-byte encodings such
+as Latin-1 and Windows-1252 can be transcoded to UTF-8 on the fly by wrapping
+the input stream in a [`DecodingReader`], which will also detect UTF-16 BOMs.
+
 */
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 #[allow(unused_macros)]
 macro_rules! brec {
     () => {{
@@ -409,39 +471,102 @@ macro_rules! srec {
     }};
 }
 
+#[cfg(feature = "tokio")]
+mod async_buffer;
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "tokio")]
+mod async_splitter;
+#[cfg(feature = "tokio")]
+mod async_writer;
+#[cfg(feature = "tokio")]
+mod async_zero_copy_reader;
 #[cfg(feature = "binary")]
 pub mod binary;
+#[cfg(feature = "std")]
 mod buffer;
+#[cfg(feature = "std")]
+mod checkpoint;
 mod core;
 mod debug;
+#[cfg(feature = "serde")]
+mod deserializer;
+mod dialect;
+#[cfg(feature = "std")]
+mod encoding;
 mod error;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
 mod ext;
+mod limits;
+#[cfg(feature = "std")]
 mod line_reader;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
 mod peeker;
+mod position;
+mod preamble;
+mod push_parser;
+#[cfg(feature = "std")]
 mod reader;
 mod records;
 mod searcher;
+#[cfg(feature = "std")]
 mod seeker;
+#[cfg(feature = "std")]
 mod select;
+#[cfg(feature = "serde")]
+mod serializer;
+#[cfg(feature = "std")]
 mod splitter;
 mod total_reader;
+#[cfg(feature = "std")]
+mod tracker;
 mod utils;
+#[cfg(feature = "std")]
 mod writer;
+#[cfg(feature = "std")]
 mod zero_copy_reader;
 
+#[cfg(feature = "std")]
+pub use checkpoint::Checkpoint;
+pub use dialect::{Terminator, Trim};
+#[cfg(feature = "std")]
+pub use encoding::{DecodingReader, Encoding};
 pub use error::{Error, ErrorKind, Result};
+#[cfg(feature = "std")]
 pub use line_reader::LineReader;
+#[cfg(feature = "std")]
+pub use parallel::{ParallelByteRecords, ParallelReader, ParallelReaderBuilder};
+#[cfg(feature = "std")]
 pub use peeker::{Peeker, PeekerBuilder};
+pub use position::Position;
+pub use push_parser::{PushParser, PushParserBuilder};
+#[cfg(feature = "std")]
 pub use reader::{Reader, ReaderBuilder, ReverseReader};
 pub use records::{ByteRecord, ZeroCopyByteRecord};
 pub use searcher::searcher_simd_instructions;
+#[cfg(feature = "std")]
 pub use seeker::{Seeker, SeekerBuilder};
+#[cfg(feature = "std")]
 pub use select::{ByteHeadersIndex, ColumIndexationBy, Selection, Selector};
+#[cfg(feature = "std")]
 pub use splitter::{Splitter, SplitterBuilder};
 pub use total_reader::{TotalReader, TotalReaderBuilder};
-pub use utils::{unescape, AppendOnlyView};
+pub use utils::{unescape, unescape_with, AppendOnlyView};
+#[cfg(feature = "std")]
 pub use writer::{Writer, WriterBuilder};
+#[cfg(feature = "std")]
 pub use zero_copy_reader::{ZeroCopyReader, ZeroCopyReaderBuilder};
 
+#[cfg(feature = "tokio")]
+pub use async_reader::AsyncReader;
+#[cfg(feature = "tokio")]
+pub use async_splitter::AsyncSplitter;
+#[cfg(feature = "tokio")]
+pub use async_writer::AsyncWriter;
+#[cfg(feature = "tokio")]
+pub use async_zero_copy_reader::AsyncZeroCopyReader;
+
 #[cfg(feature = "str")]
 pub use records::StringRecord;
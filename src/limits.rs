@@ -0,0 +1,45 @@
+use crate::error::{self, Error, ErrorKind};
+
+/// Optional safeguards against pathological records, typically found in
+/// untrusted data, shared by the builders.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) max_record_size: Option<usize>,
+    pub(crate) max_fields: Option<usize>,
+}
+
+impl Limits {
+    #[inline(always)]
+    pub(crate) fn check_record_size(&self, size: usize, pos: (u64, u64)) -> error::Result<()> {
+        match self.max_record_size {
+            Some(limit) if size > limit => Err(Error::new(ErrorKind::RecordTooLarge {
+                pos: Some(pos),
+                limit,
+            })),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the size of a record starting at `pos`, the stream being
+    /// currently at byte offset `end`.
+    ///
+    /// NOTE: `pos` must be the position of the record itself, i.e. after any
+    /// skipped empty or comment line, since those never count towards the size
+    /// of the next record.
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn check_record_span(&self, pos: (u64, u64), end: u64) -> error::Result<()> {
+        self.check_record_size((end - pos.0) as usize, pos)
+    }
+
+    #[inline(always)]
+    pub(crate) fn check_fields(&self, fields: usize, pos: (u64, u64)) -> error::Result<()> {
+        match self.max_fields {
+            Some(limit) if fields > limit => Err(Error::new(ErrorKind::TooManyFields {
+                pos: Some(pos),
+                limit,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
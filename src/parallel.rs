@@ -0,0 +1,571 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::dialect::Terminator;
+use crate::error;
+use crate::records::{ByteRecord, ZeroCopyByteRecord};
+use crate::seeker::SeekerBuilder;
+use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+// NOTE: records are sent to the ordered iterator in batches to amortize
+// synchronization, and only a few batches may be pending per segment so that
+// memory stays bounded when the consumer is slower than the workers.
+const BATCH_SIZE: usize = 256;
+const PENDING_BATCHES: usize = 4;
+
+type Opener<R> = Arc<dyn Fn() -> io::Result<R> + Send + Sync>;
+type Worker = (Receiver<error::Result<Vec<ByteRecord>>>, JoinHandle<()>);
+
+fn default_threads() -> usize {
+    #[cfg(feature = "rayon")]
+    {
+        rayon::current_num_threads()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
+}
+
+/// Builds a [`ParallelReader`] with given configuration.
+pub struct ParallelReaderBuilder {
+    seeker_builder: SeekerBuilder,
+    threads: Option<usize>,
+}
+
+impl Default for ParallelReaderBuilder {
+    fn default() -> Self {
+        Self {
+            seeker_builder: SeekerBuilder::new(),
+            threads: None,
+        }
+    }
+}
+
+impl ParallelReaderBuilder {
+    /// Create a new [`ParallelReaderBuilder`] with default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`ParallelReaderBuilder`] with provided `capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut builder = Self::default();
+        builder.buffer_capacity(capacity);
+        builder
+    }
+
+    /// Set the delimiter to be used by the created [`ParallelReader`].
+    ///
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
+    ///
+    /// Will default to a comma.
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.seeker_builder.delimiter(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`ParallelReader`].
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.seeker_builder.delimiter_bytes(delimiter);
+        self
+    }
+
+    /// Set the quote char to be used by the created [`ParallelReader`].
+    ///
+    /// This char must be a single byte.
+    ///
+    /// Will default to a double quote.
+    pub fn quote(&mut self, quote: u8) -> &mut Self {
+        self.seeker_builder.quote(quote);
+        self
+    }
+
+    /// Indicate whether the created [`ParallelReader`] should handle quoting
+    /// at all.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.seeker_builder.quoting(yes);
+        self
+    }
+
+    /// Set the escape char to be used by the created [`ParallelReader`].
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.seeker_builder.escape(escape);
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`ParallelReader`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.seeker_builder.double_quote(yes);
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`ParallelReader`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.seeker_builder.terminator(terminator);
+        self
+    }
+
+    /// Set the comment char to be used by the created [`ParallelReader`].
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.seeker_builder.comment(comment);
+        self
+    }
+
+    /// Set the capacity of the buffered readers used by each thread of the
+    /// created [`ParallelReader`].
+    ///
+    /// Will default to `8192`.
+    pub fn buffer_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.seeker_builder.buffer_capacity(capacity);
+        self
+    }
+
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`ParallelReader`].
+    ///
+    /// See [`SeekerBuilder::skip_lines`] for more detail.
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.seeker_builder.skip_lines(count);
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`ParallelReader`], as long as they satisfy the given predicate.
+    ///
+    /// See [`SeekerBuilder::skip_lines_while`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.seeker_builder.skip_lines_while(predicate);
+        self
+    }
+
+    /// Indicate whether first record must be understood as a header.
+    ///
+    /// Will default to `true`.
+    pub fn has_headers(&mut self, yes: bool) -> &mut Self {
+        self.seeker_builder.has_headers(yes);
+        self
+    }
+
+    /// Set the number of threads, and therefore the maximum number of
+    /// segments, the stream will be read with. Less segments might be used if
+    /// the stream is too small.
+    ///
+    /// Will default to the available parallelism, or to the number of threads
+    /// of the current `rayon` pool if the `rayon` feature is enabled.
+    pub fn threads(&mut self, threads: usize) -> &mut Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Create a new [`ParallelReader`] reading the file at given path, which
+    /// will be opened once per thread.
+    ///
+    /// Returns `Ok(None)` if the file does not contain any record.
+    pub fn from_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> error::Result<Option<ParallelReader<File>>> {
+        let path = path.as_ref().to_path_buf();
+
+        self.from_opener(move || File::open(&path))
+    }
+
+    /// Create a new [`ParallelReader`] using the provided function, which must
+    /// open a new independent handle on the same seekable stream each time it
+    /// is called, e.g. one per thread.
+    ///
+    /// Returns `Ok(None)` if the stream does not contain any record.
+    pub fn from_opener<R, F>(&self, open: F) -> error::Result<Option<ParallelReader<R>>>
+    where
+        R: Read + Seek,
+        F: Fn() -> io::Result<R> + Send + Sync + 'static,
+    {
+        let mut seeker = match self.seeker_builder.from_reader(open()?)? {
+            Some(seeker) => seeker,
+            None => return Ok(None),
+        };
+
+        let segments = seeker.segments(self.threads.unwrap_or_else(default_threads).max(1))?;
+
+        Ok(Some(ParallelReader {
+            open: Arc::new(open),
+            headers: seeker.byte_headers().clone(),
+            builder: Arc::new(seeker.builder),
+            segments,
+        }))
+    }
+}
+
+/// Read the given segment of the stream, passing its records to the callback
+/// until it returns `false`.
+fn read_segment<R, F>(
+    open: &Opener<R>,
+    builder: &ZeroCopyReaderBuilder,
+    (start, end): (u64, u64),
+    mut callback: F,
+) -> error::Result<()>
+where
+    R: Read + Seek,
+    F: FnMut(ZeroCopyByteRecord) -> error::Result<bool>,
+{
+    let mut inner = open()?;
+    inner.seek(SeekFrom::Start(start))?;
+
+    let mut reader = builder.from_reader(inner.take(end - start));
+
+    while let Some(record) = reader.read_byte_record()? {
+        if !callback(record)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// A CSV reader splitting a seekable stream into segments using a
+/// [`Seeker`](crate::Seeker), in order to parse them in parallel.
+///
+/// Each segment is read by its own zero-copy reader, stopping exactly at the
+/// segment end, on its own thread.
+///
+/// Records are read as if the reader was flexible, and positions reported by
+/// errors are relative to the start of the segment they were found in.
+///
+/// # Configuration
+///
+/// To configure a [`ParallelReader`], use a [`ParallelReaderBuilder`].
+pub struct ParallelReader<R> {
+    open: Opener<R>,
+    builder: Arc<ZeroCopyReaderBuilder>,
+    headers: ByteRecord,
+    segments: Vec<(u64, u64)>,
+}
+
+impl ParallelReader<File> {
+    /// Create a new parallel reader with default configuration reading the
+    /// file at given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> error::Result<Option<Self>> {
+        ParallelReaderBuilder::new().from_path(path)
+    }
+}
+
+impl<R: Read + Seek> ParallelReader<R> {
+    /// Returns the headers of the stream, or just its first record if the
+    /// reader was configured thusly.
+    pub fn byte_headers(&self) -> &ByteRecord {
+        &self.headers
+    }
+
+    /// Returns the `(start, end)` byte offsets of the segments that will be
+    /// read in parallel.
+    pub fn segments(&self) -> &[(u64, u64)] {
+        &self.segments
+    }
+
+    /// Call the given function on every record of the stream, in no
+    /// particular order, using one thread per segment, or the current `rayon`
+    /// pool if the `rayon` feature is enabled.
+    ///
+    /// Reading stops as soon as the callback returns an error, which will be
+    /// returned by this method.
+    pub fn for_each<F>(&self, callback: F) -> error::Result<()>
+    where
+        F: Fn(ZeroCopyByteRecord) -> error::Result<()> + Sync,
+    {
+        let failed = AtomicBool::new(false);
+
+        let read = |segment: (u64, u64)| {
+            let result = read_segment(&self.open, &self.builder, segment, |record| {
+                callback(record)?;
+                Ok(!failed.load(Ordering::Relaxed))
+            });
+
+            if result.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+
+            result
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            self.segments.par_iter().copied().try_for_each(read)
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            let read = &read;
+
+            thread::scope(|scope| {
+                let handles = self
+                    .segments
+                    .iter()
+                    .copied()
+                    .map(|segment| scope.spawn(move || read(segment)))
+                    .collect::<Vec<_>>();
+
+                handles.into_iter().try_for_each(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+            })
+        }
+    }
+}
+
+impl<R: Read + Seek + 'static> ParallelReader<R> {
+    /// Returns an iterator over the records of the stream, yielded in their
+    /// original order, while segments are parsed ahead on their own threads.
+    ///
+    /// Dropping the iterator will stop the threads.
+    ///
+    /// NOTE: this method always relies on std threads, even if the `rayon`
+    /// feature is enabled, because workers must block until the consumer has
+    /// caught up with them, which would starve a `rayon` pool.
+    pub fn byte_records(&self) -> ParallelByteRecords {
+        let mut receivers = Vec::with_capacity(self.segments.len());
+
+        for segment in self.segments.iter().copied() {
+            let (sender, receiver) = sync_channel(PENDING_BATCHES);
+            let open = self.open.clone();
+            let builder = self.builder.clone();
+
+            let handle = thread::spawn(move || {
+                let mut batch = Vec::with_capacity(BATCH_SIZE);
+
+                let result = read_segment(&open, &builder, segment, |record| {
+                    batch.push(record.to_byte_record());
+
+                    if batch.len() < BATCH_SIZE {
+                        return Ok(true);
+                    }
+
+                    let full_batch = std::mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+
+                    // NOTE: sending only fails when the iterator was dropped
+                    Ok(sender.send(Ok(full_batch)).is_ok())
+                });
+
+                let _ = sender.send(result.map(|_| batch));
+            });
+
+            receivers.push((receiver, handle));
+        }
+
+        ParallelByteRecords {
+            receivers: receivers.into_iter(),
+            current: None,
+            batch: Vec::new().into_iter(),
+        }
+    }
+}
+
+/// An iterator over the records of a [`ParallelReader`], in their original
+/// order.
+///
+/// Created by [`ParallelReader::byte_records`].
+pub struct ParallelByteRecords {
+    receivers: std::vec::IntoIter<Worker>,
+    current: Option<Worker>,
+    batch: std::vec::IntoIter<ByteRecord>,
+}
+
+impl Iterator for ParallelByteRecords {
+    type Item = error::Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.batch.next() {
+                return Some(Ok(record));
+            }
+
+            if self.current.is_none() {
+                self.current = Some(self.receivers.next()?);
+            }
+
+            let (receiver, _) = self.current.as_ref().unwrap();
+
+            match receiver.recv() {
+                Ok(Ok(batch)) => {
+                    self.batch = batch.into_iter();
+                }
+                Ok(Err(err)) => {
+                    self.receivers = Vec::new().into_iter();
+                    self.current = None;
+
+                    return Some(Err(err));
+                }
+                Err(_) => {
+                    // NOTE: the segment is done, but we need to make sure its
+                    // thread did not panic before moving on to the next one
+                    let (_, handle) = self.current.take().unwrap();
+
+                    if let Err(panic) = handle.join() {
+                        std::panic::resume_unwind(panic);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::error::{Error, ErrorKind};
+    use crate::reader::ReaderBuilder;
+
+    fn generate_csv(count: usize) -> Arc<[u8]> {
+        let mut csv = String::from("name,surname,notes\n");
+
+        for i in 0..count {
+            if i % 7 == 0 {
+                csv.push_str(&format!(
+                    "john{},\"landy, the \"\"{}\"\"\",\"multi\nline\"\n",
+                    i, i
+                ));
+            } else {
+                csv.push_str(&format!("lucy{},rose,{}\n", i, i));
+            }
+        }
+
+        csv.into_bytes().into()
+    }
+
+    fn parallel_reader(csv: &Arc<[u8]>, threads: usize) -> ParallelReader<Cursor<Arc<[u8]>>> {
+        let csv = csv.clone();
+
+        ParallelReaderBuilder::new()
+            .threads(threads)
+            .from_opener(move || Ok(Cursor::new(csv.clone())))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_byte_records() -> error::Result<()> {
+        let csv = generate_csv(2000);
+        let expected = ReaderBuilder::new()
+            .from_reader(&csv[..])
+            .byte_records()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for threads in [1, 4, 7] {
+            let reader = parallel_reader(&csv, threads);
+
+            assert_eq!(reader.segments().len(), threads);
+            assert_eq!(reader.byte_headers(), &brec!["name", "surname", "notes"]);
+
+            let records = reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(records, expected);
+
+            // Dropping the iterator early must not hang
+            assert_eq!(reader.byte_records().take(3).count(), 3);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_each() -> error::Result<()> {
+        let csv = generate_csv(2000);
+        let mut expected = ReaderBuilder::new()
+            .from_reader(&csv[..])
+            .byte_records()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        expected.sort_by(|a, b| a.iter().cmp(b.iter()));
+
+        let reader = parallel_reader(&csv, 4);
+        let records = Mutex::new(Vec::new());
+
+        reader.for_each(|record| {
+            records.lock().unwrap().push(record.to_byte_record());
+            Ok(())
+        })?;
+
+        let mut records = records.into_inner().unwrap();
+        records.sort_by(|a, b| a.iter().cmp(b.iter()));
+
+        assert_eq!(records, expected);
+
+        // Errors must stop the reading
+        let count = AtomicU64::new(0);
+
+        let result = reader.for_each(|_| {
+            if count.fetch_add(1, Ordering::Relaxed) == 10 {
+                return Err(Error::new(ErrorKind::UnquotableCell));
+            }
+
+            Ok(())
+        });
+
+        assert!(matches!(
+            result.unwrap_err().kind(),
+            ErrorKind::UnquotableCell
+        ));
+        assert!(count.into_inner() < 2000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_small_stream() -> error::Result<()> {
+        let csv: Arc<[u8]> = b"name\njohn\nlucy\n".as_slice().into();
+        let reader = parallel_reader(&csv, 4);
+
+        assert_eq!(reader.segments(), &[(5, 15)]);
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["john"], brec!["lucy"]]
+        );
+
+        let csv: Arc<[u8]> = b"name\n".as_slice().into();
+
+        assert!(ParallelReaderBuilder::new()
+            .from_opener(move || Ok(Cursor::new(csv.clone())))?
+            .is_none());
+
+        Ok(())
+    }
+}
@@ -1,15 +1,19 @@
 use std::io::{Chain, Cursor, Read};
+use std::sync::Arc;
 
 use crate::buffer::ScratchBuffer;
 use crate::core::{CoreReader, ReadResult};
+use crate::dialect::{Delimiter, Dialect, Terminator};
 use crate::error;
+use crate::limits::Limits;
+use crate::preamble::SkipLines;
 use crate::records::{ByteRecord, ZeroCopyByteRecord};
 use crate::utils::trim_bom;
 
 /// Builds a [`Peeker`] with given configuration.
 pub struct PeekerBuilder {
-    delimiter: u8,
-    quote: u8,
+    dialect: Dialect,
+    limits: Limits,
     buffer_capacity: usize,
     has_headers: bool,
 }
@@ -17,8 +21,8 @@ pub struct PeekerBuilder {
 impl Default for PeekerBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
+            limits: Limits::default(),
             buffer_capacity: 8192,
             has_headers: true,
         }
@@ -40,11 +44,27 @@ impl PeekerBuilder {
 
     /// Set the delimiter to be used by the created [`Peeker`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`Peeker`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -54,7 +74,82 @@ impl PeekerBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`Peeker`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`Peeker`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`Peeker`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`Peeker`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`Peeker`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Set the maximum number of bytes a single record may span in the stream
+    /// read by the created [`Peeker`]. Reading a larger record will return an
+    /// [`ErrorKind::RecordTooLarge`](crate::ErrorKind::RecordTooLarge) error
+    /// instead of buffering it indefinitely.
+    ///
+    /// Will default to no limit.
+    pub fn max_record_size(&mut self, size: usize) -> &mut Self {
+        self.limits.max_record_size = Some(size);
+        self
+    }
+
+    /// Set the maximum number of fields a single record may have when read
+    /// by the created [`Peeker`]. Reading a record with more fields will return an
+    /// [`ErrorKind::TooManyFields`](crate::ErrorKind::TooManyFields) error.
+    ///
+    /// Will default to no limit.
+    pub fn max_fields(&mut self, fields: usize) -> &mut Self {
+        self.limits.max_fields = Some(fields);
         self
     }
 
@@ -64,6 +159,34 @@ impl PeekerBuilder {
         self
     }
 
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`Peeker`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`Peeker::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`Peeker`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
+        self
+    }
+
     /// Indicate whether first record must be understood as a header.
     ///
     /// Will default to `true`.
@@ -77,9 +200,13 @@ impl PeekerBuilder {
     pub fn from_reader<R: Read>(&self, reader: R) -> Peeker<R> {
         Peeker {
             buffer: ScratchBuffer::with_capacity(self.buffer_capacity, reader),
-            inner: CoreReader::new(self.delimiter, self.quote),
+            inner: CoreReader::new(&self.dialect),
+            limits: self.limits,
             headers: ByteRecord::new(),
             rest: Vec::new(),
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
             has_headers: self.has_headers,
             has_read: false,
             has_crlf_newlines: false,
@@ -92,8 +219,12 @@ impl PeekerBuilder {
 pub struct Peeker<R> {
     buffer: ScratchBuffer<R>,
     inner: CoreReader,
+    limits: Limits,
     headers: ByteRecord,
     rest: Vec<u8>,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
     has_headers: bool,
     has_read: bool,
     has_crlf_newlines: bool,
@@ -125,16 +256,40 @@ impl<R: Read> Peeker<R> {
                     self.buffer.consume(pos);
                     return Ok(false);
                 }
-                Cr | Lf => {
+                Cr | Lf | Comment => {
                     self.buffer.consume(pos);
                 }
                 InputEmpty => {
                     self.buffer.save();
+
+                    self.limits
+                        .check_record_size(self.buffer.saved().len(), (0, 0))?;
+
+                    // NOTE: separators cannot be counted before being verified
+                    if !self.inner.delimiter.is_multi_byte() {
+                        self.limits.check_fields(seps.len() + 1, (0, 0))?;
+                    }
                 }
+                MalformedQuote | UnterminatedQuote => unreachable!("peeker cannot be strict"),
                 Record => {
+                    let size = self.buffer.saved().len() + pos;
+
+                    self.limits.check_record_size(size, (0, 0))?;
+
                     let bytes = self.buffer.flush(pos);
+                    let slice = self.inner.trim_terminator(bytes);
+
+                    self.inner.delimiter.filter_separators(slice, &mut seps);
+                    self.limits.check_fields(seps.len() + 1, (0, 0))?;
 
-                    let record = ZeroCopyByteRecord::new(bytes, &seps, self.inner.quote);
+                    let record = ZeroCopyByteRecord::new(
+                        slice,
+                        &seps,
+                        self.inner.delimiter,
+                        self.inner.terminator(),
+                        self.inner.quoting(),
+                        false,
+                    );
 
                     if bytes.len().saturating_sub(2) == record.as_slice().len() {
                         self.has_crlf_newlines = true;
@@ -158,6 +313,10 @@ impl<R: Read> Peeker<R> {
         let input = self.buffer.fill_buf()?;
         let bom_len = trim_bom(input);
         self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        self.preamble = self.skip_lines.skip(self.buffer.inner_mut(), &self.inner)?;
 
         // Reading headers
         let has_data = self.read_byte_record_impl()?;
@@ -203,6 +362,24 @@ impl<R: Read> Peeker<R> {
         Ok(&self.rest)
     }
 
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// first record, terminators included. Those lines will not be emitted
+    /// again by [`Peeker::into_reader`].
+    pub fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read()?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read()?;
+
+        Ok(self.had_bom)
+    }
+
     pub fn into_reader(mut self) -> Chain<Cursor<Vec<u8>>, R> {
         let bufreader = self.buffer.into_bufreader();
 
@@ -219,6 +396,7 @@ impl<R: Read> Peeker<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ErrorKind;
 
     #[test]
     fn test_peeker() -> error::Result<()> {
@@ -228,7 +406,7 @@ mod tests {
         let mut peeker = Peeker::from_reader(&b"name,surname\nhello,world\njohn,lucy"[..]);
 
         assert_eq!(peeker.peek_byte_record()?, &brec!["name", "surname"]);
-        assert_eq!(peeker.has_crlf_newlines()?, false);
+        assert!(!peeker.has_crlf_newlines()?);
 
         peeker.into_reader().read_to_end(&mut buffer)?;
         assert_eq!(&buffer, b"hello,world\njohn,lucy");
@@ -237,7 +415,7 @@ mod tests {
         let mut peeker = Peeker::from_reader(&b"name,surname\r\nhello,world\r\njohn,lucy"[..]);
 
         assert_eq!(peeker.peek_byte_record()?, &brec!["name", "surname"]);
-        assert_eq!(peeker.has_crlf_newlines()?, true);
+        assert!(peeker.has_crlf_newlines()?);
 
         buffer.clear();
         peeker.into_reader().read_to_end(&mut buffer)?;
@@ -249,7 +427,7 @@ mod tests {
             .from_reader(&b"bonjour,le monde\nhello,world\njohn,lucy"[..]);
 
         assert_eq!(peeker.peek_byte_record()?, &brec!["bonjour", "le monde"]);
-        assert_eq!(peeker.has_crlf_newlines()?, false);
+        assert!(!peeker.has_crlf_newlines()?);
 
         buffer.clear();
         peeker.into_reader().read_to_end(&mut buffer)?;
@@ -261,7 +439,7 @@ mod tests {
             .from_reader(&b"bonjour,le monde\r\nhello,world\r\njohn,lucy"[..]);
 
         assert_eq!(peeker.peek_byte_record()?, &brec!["bonjour", "le monde"]);
-        assert_eq!(peeker.has_crlf_newlines()?, true);
+        assert!(peeker.has_crlf_newlines()?);
 
         buffer.clear();
         peeker.into_reader().read_to_end(&mut buffer)?;
@@ -269,4 +447,55 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_limits() {
+        let data = format!("\"name,surname\n{}", "john,lucy\n".repeat(32));
+
+        let mut peeker = PeekerBuilder::with_capacity(16)
+            .max_record_size(64)
+            .from_reader(Cursor::new(&data));
+
+        assert!(matches!(
+            peeker.peek_byte_record().unwrap_err().kind(),
+            ErrorKind::RecordTooLarge { limit: 64, .. }
+        ));
+
+        let mut peeker = PeekerBuilder::new()
+            .max_fields(1)
+            .from_reader(Cursor::new(&data[1..]));
+
+        assert!(matches!(
+            peeker.peek_byte_record().unwrap_err().kind(),
+            ErrorKind::TooManyFields { limit: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_multi_byte_delimiter() -> error::Result<()> {
+        let mut peeker = PeekerBuilder::new()
+            .delimiter_bytes(b"||")
+            .max_fields(2)
+            .from_reader(Cursor::new("name|||surname\njohn||lucy"));
+
+        assert_eq!(peeker.peek_byte_record()?, &brec!["name", "|surname"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_lines() -> error::Result<()> {
+        let mut peeker = PeekerBuilder::new()
+            .skip_lines(1)
+            .from_reader(Cursor::new("sep=,\nname,surname\njohn,lucy"));
+
+        assert_eq!(peeker.preamble()?, b"sep=,\n");
+        assert_eq!(peeker.peek_byte_record()?, &brec!["name", "surname"]);
+
+        let mut buffer = Vec::new();
+        peeker.into_reader().read_to_end(&mut buffer)?;
+        assert_eq!(&buffer, b"john,lucy");
+
+        Ok(())
+    }
 }
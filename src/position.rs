@@ -0,0 +1,34 @@
+/// The location of a record in a CSV stream.
+///
+/// See [`ByteRecord::position`](crate::ByteRecord::position) and
+/// [`ZeroCopyByteRecord::position`](crate::ZeroCopyByteRecord::position).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Byte offset of the first byte of the record in the stream.
+    pub byte: u64,
+    /// Line of the first byte of the record, starting at 1. Lines are
+    /// physical, i.e. every terminator byte counts, e.g. LF for the default
+    /// [`Terminator::CRLF`](crate::Terminator::CRLF), including the ones
+    /// embedded in quoted fields.
+    pub line: u64,
+    /// Index of the record, starting at 0. The header row, if any, is the
+    /// record at index 0.
+    pub record: u64,
+}
+
+impl Position {
+    /// Create a new position pointing at the very beginning of a stream.
+    pub fn new() -> Self {
+        Self {
+            byte: 0,
+            line: 1,
+            record: 0,
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,214 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use memchr::memchr;
+
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncRead;
+
+#[cfg(feature = "tokio")]
+use crate::async_buffer::AsyncBufReaderWithPosition;
+#[cfg(feature = "std")]
+use crate::buffer::{BufReaderWithPosition, Buffered, Positioned};
+use crate::core::CoreReader;
+
+type LinePredicate = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// How to skip the preamble lines found before the header row.
+///
+/// Preamble lines are raw lines, split on the record terminator without any
+/// regard for quoting.
+#[derive(Clone, Default)]
+pub(crate) enum SkipLines {
+    #[default]
+    None,
+    Count(usize),
+    While(LinePredicate),
+}
+
+impl fmt::Debug for SkipLines {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Count(count) => f.debug_tuple("Count").field(count).finish(),
+            Self::While(_) => write!(f, "While(..)"),
+        }
+    }
+}
+
+impl SkipLines {
+    /// Skips the preamble lines from the given buffer and returns them.
+    ///
+    /// When a predicate is used, the first line failing it is given back to
+    /// the buffer so that it can be read as the header row.
+    #[cfg(feature = "std")]
+    pub(crate) fn skip<R: Read>(
+        &self,
+        buffer: &mut BufReaderWithPosition<R>,
+        core: &CoreReader,
+    ) -> io::Result<Vec<u8>> {
+        let mut preamble = Vec::new();
+
+        match self {
+            Self::None => (),
+            Self::Count(count) => {
+                for _ in 0..*count {
+                    if !read_line(buffer, core.newline(), &mut preamble)? {
+                        break;
+                    }
+                }
+            }
+            Self::While(predicate) => loop {
+                let start = preamble.len();
+
+                if !read_line(buffer, core.newline(), &mut preamble)? {
+                    break;
+                }
+
+                if !predicate(core.trim_terminator(&preamble[start..])) {
+                    buffer.unread(&preamble[start..]);
+                    preamble.truncate(start);
+                    break;
+                }
+            },
+        };
+
+        Ok(preamble)
+    }
+
+    /// Async counterpart of [`Self::skip`].
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn skip_async<R: AsyncRead + Unpin>(
+        &self,
+        buffer: &mut AsyncBufReaderWithPosition<R>,
+        core: &CoreReader,
+    ) -> io::Result<Vec<u8>> {
+        let mut preamble = Vec::new();
+
+        match self {
+            Self::None => (),
+            Self::Count(count) => {
+                for _ in 0..*count {
+                    if !read_line_async(buffer, core.newline(), &mut preamble).await? {
+                        break;
+                    }
+                }
+            }
+            Self::While(predicate) => loop {
+                let start = preamble.len();
+
+                if !read_line_async(buffer, core.newline(), &mut preamble).await? {
+                    break;
+                }
+
+                if !predicate(core.trim_terminator(&preamble[start..])) {
+                    buffer.unread(&preamble[start..]);
+                    preamble.truncate(start);
+                    break;
+                }
+            },
+        };
+
+        Ok(preamble)
+    }
+
+    /// Returns the length of the preamble found at the beginning of the given
+    /// bytes.
+    pub(crate) fn skip_bytes(&self, bytes: &[u8], core: &CoreReader) -> usize {
+        let mut pos = 0;
+
+        let next_line = |pos: usize| -> Option<usize> {
+            if pos >= bytes.len() {
+                return None;
+            }
+
+            Some(match memchr(core.newline(), &bytes[pos..]) {
+                Some(offset) => pos + offset + 1,
+                None => bytes.len(),
+            })
+        };
+
+        match self {
+            Self::None => (),
+            Self::Count(count) => {
+                for _ in 0..*count {
+                    match next_line(pos) {
+                        Some(end) => pos = end,
+                        None => break,
+                    }
+                }
+            }
+            Self::While(predicate) => {
+                while let Some(end) = next_line(pos) {
+                    if !predicate(core.trim_terminator(&bytes[pos..end])) {
+                        break;
+                    }
+
+                    pos = end;
+                }
+            }
+        };
+
+        pos
+    }
+}
+
+// Moves the buffered bytes of the current line into `line`, and returns
+// whether its terminator was found
+#[cfg(feature = "std")]
+#[inline]
+fn take_line<B: Buffered>(buffer: &mut Positioned<B>, newline: u8, line: &mut Vec<u8>) -> bool {
+    let input = buffer.buffer();
+
+    let (len, found) = match memchr(newline, input) {
+        Some(offset) => (offset + 1, true),
+        None => (input.len(), false),
+    };
+
+    line.extend_from_slice(&input[..len]);
+    buffer.consume(len);
+
+    found
+}
+
+// Returns whether a line, possibly not terminated, could be read
+#[cfg(feature = "std")]
+fn read_line<R: Read>(
+    buffer: &mut BufReaderWithPosition<R>,
+    newline: u8,
+    line: &mut Vec<u8>,
+) -> io::Result<bool> {
+    let mut found = false;
+
+    while !buffer.fill_buf()?.is_empty() {
+        found = true;
+
+        if take_line(buffer, newline, line) {
+            break;
+        }
+    }
+
+    Ok(found)
+}
+
+#[cfg(feature = "tokio")]
+async fn read_line_async<R: AsyncRead + Unpin>(
+    buffer: &mut AsyncBufReaderWithPosition<R>,
+    newline: u8,
+    line: &mut Vec<u8>,
+) -> io::Result<bool> {
+    let mut found = false;
+
+    while !buffer.fill_buf().await?.is_empty() {
+        found = true;
+
+        if take_line(buffer, newline, line) {
+            break;
+        }
+    }
+
+    Ok(found)
+}
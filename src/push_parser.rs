@@ -0,0 +1,705 @@
+use alloc::vec::Vec;
+
+use crate::core::{CoreReader, ReadResult};
+use crate::dialect::{Delimiter, Dialect, Terminator};
+use crate::error::{self, Error, ErrorKind};
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::records::{ByteRecord, ZeroCopyByteRecord};
+use crate::utils::count_lines;
+
+/// Builds a [`PushParser`] with given configuration.
+#[derive(Default)]
+pub struct PushParserBuilder {
+    dialect: Dialect,
+    limits: Limits,
+}
+
+impl PushParserBuilder {
+    /// Create a new [`PushParserBuilder`] with default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the delimiter to be used by the created [`PushParser`].
+    ///
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
+    ///
+    /// Will default to a comma.
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`PushParser`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
+        self
+    }
+
+    /// Set the quote char to be used by the created [`PushParser`].
+    ///
+    /// This char must be a single byte.
+    ///
+    /// Will default to a double quote.
+    pub fn quote(&mut self, quote: u8) -> &mut Self {
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`PushParser`] should handle quoting at
+    /// all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`PushParser`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`PushParser`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`PushParser`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`PushParser`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Indicate whether the created [`PushParser`] should be strict regarding
+    /// quoting, i.e. whether it should return an error when finding a quote
+    /// in the middle of an unquoted field, or unexpected data after a closing
+    /// quote, instead of trying its best to make sense of it.
+    ///
+    /// A strict [`PushParser`] will also return an error when finished in the
+    /// middle of a quoted field, which typically happens when given truncated
+    /// data. A non-strict one will yield the truncated record, the way the
+    /// other readers do, but [`PushParser::ended_in_quote`] can be checked
+    /// afterwards.
+    ///
+    /// Will default to `false`.
+    pub fn strict(&mut self, yes: bool) -> &mut Self {
+        self.dialect.strict = yes;
+        self
+    }
+
+    /// Set the maximum number of bytes a single record may span in the data
+    /// fed to the created [`PushParser`]. Reading a larger record will return
+    /// an [`ErrorKind::RecordTooLarge`](crate::ErrorKind::RecordTooLarge)
+    /// error instead of buffering it indefinitely.
+    ///
+    /// Will default to no limit.
+    pub fn max_record_size(&mut self, size: usize) -> &mut Self {
+        self.limits.max_record_size = Some(size);
+        self
+    }
+
+    /// Set the maximum number of fields a single record may have when parsed
+    /// by the created [`PushParser`]. Reading a record with more fields will
+    /// return an [`ErrorKind::TooManyFields`](crate::ErrorKind::TooManyFields)
+    /// error.
+    ///
+    /// Will default to no limit.
+    pub fn max_fields(&mut self, fields: usize) -> &mut Self {
+        self.limits.max_fields = Some(fields);
+        self
+    }
+
+    /// Indicate whether the created [`PushParser`] should skip empty lines.
+    ///
+    /// When set to `false`, empty lines will be yielded as records having a
+    /// single empty field.
+    ///
+    /// Will default to `true`.
+    pub fn skip_empty_lines(&mut self, yes: bool) -> &mut Self {
+        self.dialect.skip_empty_lines = yes;
+        self
+    }
+
+    /// Indicate whether the created [`PushParser`] should only consider quotes
+    /// found at the very beginning of a field as opening a quoted field, like
+    /// the `csv` crate does, instead of entering the quoted state on any
+    /// quote. This way, dirty data such as `joh"n` won't misalign records.
+    ///
+    /// This mode is slightly slower and makes [`Self::strict`] only report
+    /// unexpected data after closing quotes.
+    ///
+    /// Will default to `false`.
+    pub fn lenient_quotes(&mut self, yes: bool) -> &mut Self {
+        self.dialect.lenient_quotes = yes;
+        self
+    }
+
+    /// Create a new [`PushParser`].
+    pub fn build(&self) -> PushParser {
+        PushParser {
+            inner: CoreReader::new(&self.dialect),
+            limits: self.limits,
+            buffer: Vec::new(),
+            record_start: 0,
+            scanned: 0,
+            seps: Vec::new(),
+            offset: 0,
+            start: Position::new(),
+            line: 1,
+            index: 0,
+            finished: false,
+        }
+    }
+}
+
+/// An already configured push-based CSV parser, for when data cannot be
+/// handed over as a [`std::io::Read`] stream, e.g. when it arrives through
+/// callbacks.
+///
+/// Chunks of data are given to the parser using [`Self::feed`], and complete
+/// records can then be retrieved using [`Self::read_byte_record`] or
+/// [`Self::read_zero_copy_byte_record`], until they return nothing, meaning
+/// more data is required. Once all data has been fed, [`Self::finish`] must be
+/// called so that the last record, which may lack a terminator, can be read.
+///
+/// Note that the parser considers the data as a bare CSV stream: headers are
+/// not handled specifically and BOMs are not trimmed.
+///
+/// # Configuration
+///
+/// To configure a [`PushParser`], use the [`PushParserBuilder`].
+///
+/// # Example
+///
+/// ```
+/// use simd_csv::PushParser;
+///
+/// let mut parser = PushParser::new();
+/// let mut names = Vec::new();
+///
+/// for chunk in ["name,age\njo", "hn,45\nlucy,", "67"] {
+///     parser.feed(chunk.as_bytes());
+///
+///     while let Some(record) = parser.read_zero_copy_byte_record()? {
+///         names.push(record.unquote(0).unwrap().to_vec());
+///     }
+/// }
+///
+/// parser.finish();
+///
+/// while let Some(record) = parser.read_zero_copy_byte_record()? {
+///     names.push(record.unquote(0).unwrap().to_vec());
+/// }
+///
+/// assert_eq!(names, [&b"name"[..], b"john", b"lucy"]);
+/// # Ok::<(), simd_csv::Error>(())
+/// ```
+pub struct PushParser {
+    inner: CoreReader,
+    limits: Limits,
+    buffer: Vec<u8>,
+    // NOTE: offset of the current record in the buffer
+    record_start: usize,
+    // NOTE: offset of the first byte of the buffer not yet given to the
+    // inner reader
+    scanned: usize,
+    seps: Vec<usize>,
+    // NOTE: byte offset of the beginning of the buffer in the fed data
+    offset: u64,
+    start: Position,
+    line: u64,
+    index: u64,
+    finished: bool,
+}
+
+impl PushParser {
+    /// Create a new push parser with default configuration.
+    pub fn new() -> Self {
+        PushParserBuilder::new().build()
+    }
+
+    /// Feed the parser a new chunk of data.
+    ///
+    /// # Panics
+    ///
+    /// If [`Self::finish`] was already called.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        assert!(!self.finished, "cannot feed a finished parser");
+
+        // NOTE: data before the current record is not needed anymore, but it
+        // is only discarded when the buffer would otherwise have to grow, and
+        // when it is at least as large as the data that must be moved, so
+        // that copying remains linear in the fed data overall
+        if self.record_start > 0
+            && self.buffer.capacity() - self.buffer.len() < chunk.len()
+            && self.record_start >= self.buffered()
+        {
+            self.buffer.drain(..self.record_start);
+            self.offset += self.record_start as u64;
+            self.scanned -= self.record_start;
+            self.record_start = 0;
+        }
+
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Indicate that all data has been fed, so that the last record can be
+    /// read even if it lacks a terminator.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns whether [`Self::finish`] was called.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns whether the parser was finished in the middle of a quoted
+    /// field, meaning the last record was most likely truncated.
+    ///
+    /// A strict parser returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one still yields the record.
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
+    /// Returns the number of bytes fed to the parser but not yet yielded as
+    /// part of a record.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.record_start
+    }
+
+    /// Returns the byte offset, in the fed data, of the next record.
+    pub fn position(&self) -> u64 {
+        self.offset + self.record_start as u64
+    }
+
+    fn parse_next(&mut self) -> error::Result<Option<usize>> {
+        use ReadResult::*;
+
+        if self.scanned == self.record_start {
+            self.seps.clear();
+            self.start = Position {
+                byte: self.position(),
+                line: self.line,
+                record: self.index,
+            };
+        }
+
+        loop {
+            // NOTE: empty input means the end of the stream to the inner reader
+            if self.scanned == self.buffer.len() && !self.finished {
+                return Ok(None);
+            }
+
+            let seps_offset = self.scanned - self.record_start;
+            let input = &self.buffer[self.scanned..];
+
+            let (result, pos) =
+                self.inner
+                    .split_record_and_find_separators(input, seps_offset, &mut self.seps);
+
+            let byte = self.start.byte;
+
+            match result {
+                End => return Ok(None),
+                Cr | Lf | Comment => {
+                    self.line += self.inner.lines_read(&input[..pos]);
+                    self.scanned += pos;
+                    self.record_start = self.scanned;
+
+                    self.start.byte = self.position();
+                    self.start.line = self.line;
+                }
+                InputEmpty => {
+                    self.scanned += pos;
+
+                    let pos = (byte, self.index);
+
+                    self.limits.check_record_size(self.buffered(), pos)?;
+
+                    // NOTE: separators cannot be counted before being verified
+                    if !self.inner.delimiter.is_multi_byte() {
+                        self.limits.check_fields(self.seps.len() + 1, pos)?;
+                    }
+                }
+                MalformedQuote => {
+                    // NOTE: the core reader skips the rest of the line from there
+                    self.scanned += pos;
+                    self.line += count_lines(
+                        &self.buffer[self.record_start..self.scanned],
+                        self.inner.newline(),
+                    );
+                    self.record_start = self.scanned;
+
+                    let err = Error::new(ErrorKind::MalformedQuote {
+                        pos: Some((self.position(), self.index)),
+                    });
+
+                    self.index += 1;
+
+                    return Err(err);
+                }
+                UnterminatedQuote => {
+                    return Err(Error::new(ErrorKind::UnterminatedQuote {
+                        pos: Some((byte, self.index)),
+                    }));
+                }
+                Record => {
+                    self.scanned += pos;
+
+                    let record_end = self.scanned;
+
+                    self.line += self
+                        .inner
+                        .lines_read(&self.buffer[self.record_start..record_end]);
+
+                    self.limits
+                        .check_record_size(record_end - self.record_start, (byte, self.index))?;
+
+                    if self.inner.delimiter.is_multi_byte() {
+                        let slice = self
+                            .inner
+                            .trim_terminator(&self.buffer[self.record_start..record_end]);
+
+                        self.inner
+                            .delimiter
+                            .filter_separators(slice, &mut self.seps);
+                    }
+
+                    self.limits
+                        .check_fields(self.seps.len() + 1, (byte, self.index))?;
+
+                    self.index += 1;
+
+                    return Ok(Some(record_end));
+                }
+            }
+        }
+    }
+
+    /// Attempt to parse the next complete record and return it as a
+    /// [`ZeroCopyByteRecord`].
+    ///
+    /// Returns `Ok(None)` when more data must be fed to the parser, or when
+    /// it has been finished and all records were read.
+    pub fn read_zero_copy_byte_record(&mut self) -> error::Result<Option<ZeroCopyByteRecord<'_>>> {
+        let record_end = match self.parse_next()? {
+            None => return Ok(None),
+            Some(record_end) => record_end,
+        };
+
+        let record_start = core::mem::replace(&mut self.record_start, record_end);
+
+        Ok(Some(
+            ZeroCopyByteRecord::new(
+                self.inner
+                    .trim_terminator(&self.buffer[record_start..record_end]),
+                &self.seps,
+                self.inner.delimiter,
+                self.inner.terminator(),
+                self.inner.quoting(),
+                self.inner.trim,
+            )
+            .with_position(self.start),
+        ))
+    }
+
+    /// Attempt to parse the next complete record into a pre-allocated
+    /// [`ByteRecord`].
+    ///
+    /// Returns a boolean indicating whether a record was actually read, or if
+    /// more data must be fed to the parser, or if it has been finished and all
+    /// records were read.
+    pub fn read_byte_record(&mut self, record: &mut ByteRecord) -> error::Result<bool> {
+        match self.read_zero_copy_byte_record()? {
+            None => Ok(false),
+            Some(zero_copy_record) => {
+                zero_copy_record.read_byte_record(record);
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "std")]
+    use crate::reader::ReaderBuilder;
+
+    fn parse_in_chunks(builder: &PushParserBuilder, data: &[u8], size: usize) -> Vec<ByteRecord> {
+        let mut parser = builder.build();
+        let mut records = Vec::new();
+        let mut record = ByteRecord::new();
+
+        for chunk in data.chunks(size) {
+            parser.feed(chunk);
+
+            while parser.read_byte_record(&mut record).unwrap() {
+                records.push(record.clone());
+            }
+        }
+
+        parser.finish();
+
+        while parser.read_byte_record(&mut record).unwrap() {
+            records.push(record.clone());
+        }
+
+        assert_eq!(parser.buffered(), 0);
+        assert_eq!(parser.position(), data.len() as u64);
+
+        records
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_feed() -> error::Result<()> {
+        let data = "name,surname\n\"john\",\"landy, the \"\"everlasting\"\" bastard\"\n# comment\nlucy,\"rose\nmary\"\r\n\njermaine,jackson";
+
+        let mut builder = PushParserBuilder::new();
+        builder.comment(Some(b'#'));
+
+        let expected = ReaderBuilder::new()
+            .has_headers(false)
+            .comment(Some(b'#'))
+            .from_reader(data.as_bytes())
+            .byte_records()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let records = parse_in_chunks(&builder, data.as_bytes(), capacity);
+
+            assert_eq!(records, expected);
+            assert_eq!(
+                records
+                    .iter()
+                    .map(|record| *record.position().unwrap())
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|record| *record.position().unwrap())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_compaction() -> error::Result<()> {
+        let mut parser = PushParser::new();
+
+        for i in 0..1000u64 {
+            parser.feed(b"a,b\n");
+
+            let record = parser.read_zero_copy_byte_record()?.unwrap();
+
+            assert_eq!(record.to_byte_record(), brec!["a", "b"]);
+            assert_eq!(record.position().map(|pos| pos.byte), Some(i * 4));
+        }
+
+        assert!(parser.buffer.capacity() <= 16);
+
+        // Unread records must be kept as is
+        parser.feed(b"c,d\ne,f\n");
+        parser.feed(b"g,");
+        parser.feed(b"h\n");
+        parser.finish();
+
+        let mut records = Vec::new();
+
+        while let Some(record) = parser.read_zero_copy_byte_record()? {
+            records.push(record.to_byte_record());
+        }
+
+        assert_eq!(
+            records,
+            vec![brec!["c", "d"], brec!["e", "f"], brec!["g", "h"]]
+        );
+        assert_eq!(parser.position(), 4012);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_multi_byte_delimiter() -> error::Result<()> {
+        let mut builder = PushParserBuilder::new();
+        builder.delimiter_bytes(b"||");
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            assert_eq!(
+                parse_in_chunks(&builder, b"a||b|c\n\"d||\"||e\n", capacity),
+                vec![brec!["a", "b|c"], brec!["d||", "e"]]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish() -> error::Result<()> {
+        let mut parser = PushParser::new();
+
+        parser.feed(b"a,b\nc,d");
+        assert_eq!(
+            parser
+                .read_zero_copy_byte_record()?
+                .unwrap()
+                .to_byte_record(),
+            brec!["a", "b"]
+        );
+        assert!(parser.read_zero_copy_byte_record()?.is_none());
+        assert_eq!(parser.buffered(), 3);
+
+        parser.finish();
+        assert!(parser.is_finished());
+        assert_eq!(
+            parser
+                .read_zero_copy_byte_record()?
+                .unwrap()
+                .to_byte_record(),
+            brec!["c", "d"]
+        );
+        assert!(parser.read_zero_copy_byte_record()?.is_none());
+
+        let mut parser = PushParserBuilder::new().strict(true).build();
+        parser.feed(b"a,\"b");
+        parser.finish();
+        assert!(matches!(
+            parser.read_zero_copy_byte_record().unwrap_err().kind(),
+            ErrorKind::UnterminatedQuote { .. }
+        ));
+        assert!(parser.ended_in_quote());
+
+        let mut parser = PushParser::new();
+        parser.feed(b"a,\"b");
+        parser.finish();
+        assert!(parser.read_zero_copy_byte_record()?.is_some());
+        assert!(parser.ended_in_quote());
+
+        let mut parser = PushParserBuilder::new().max_record_size(4).build();
+        parser.feed(b"abc");
+        assert!(parser.read_zero_copy_byte_record()?.is_none());
+        parser.feed(b"def");
+        assert!(matches!(
+            parser.read_zero_copy_byte_record().unwrap_err().kind(),
+            ErrorKind::RecordTooLarge { .. }
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_truncated() -> error::Result<()> {
+        let builder = PushParserBuilder::new();
+
+        for data in [
+            "a,\"d",
+            "a,\"d\"\"e",
+            "name,notes\njohn,\"hello \"\"world\"\"\"\nlucy,\"rose\nmary",
+        ] {
+            let expected = ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(data.as_bytes())
+                .byte_records()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for capacity in [32usize, 4, 3, 2, 1] {
+                assert_eq!(
+                    parse_in_chunks(&builder, data.as_bytes(), capacity),
+                    expected
+                );
+            }
+        }
+
+        assert_eq!(
+            parse_in_chunks(&builder, b"a,\"d", 32),
+            vec![brec!["a", "d"]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict() -> error::Result<()> {
+        let mut parser = PushParserBuilder::new().strict(true).build();
+        parser.feed(b"a,b\nc,d\"e\nf,g\n");
+        parser.finish();
+
+        assert_eq!(
+            parser
+                .read_zero_copy_byte_record()?
+                .unwrap()
+                .to_byte_record(),
+            brec!["a", "b"]
+        );
+        assert!(matches!(
+            parser.read_zero_copy_byte_record().unwrap_err().kind(),
+            ErrorKind::MalformedQuote { pos: Some((7, 1)) }
+        ));
+
+        let record = parser.read_zero_copy_byte_record()?.unwrap();
+
+        assert_eq!(record.to_byte_record(), brec!["f", "g"]);
+        assert_eq!(record.position().map(|pos| pos.record), Some(2));
+        assert!(parser.read_zero_copy_byte_record()?.is_none());
+
+        Ok(())
+    }
+}
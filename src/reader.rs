@@ -1,32 +1,49 @@
-use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
 
 use crate::buffer::BufReaderWithPosition;
+use crate::checkpoint::Checkpoint;
 use crate::core::{CoreReader, ReadResult};
+use crate::dialect::{Delimiter, Dialect, Terminator, Trim};
 use crate::error::{self, Error, ErrorKind};
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::preamble::SkipLines;
 use crate::records::{ByteRecord, ByteRecordBuilder};
 use crate::select::{Selection, Selector};
-use crate::utils::{self, trim_bom};
+use crate::tracker::{Step, Tracker};
+use crate::utils::{self, count_lines, trim_bom};
 
 #[cfg(feature = "str")]
 use crate::records::StringRecord;
+#[cfg(feature = "serde")]
+use crate::select::ByteHeadersIndex;
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use std::marker::PhantomData;
 
 /// Builds a [`Reader`] with given configuration.
 pub struct ReaderBuilder {
-    delimiter: u8,
-    quote: u8,
-    buffer_capacity: usize,
-    flexible: bool,
-    has_headers: bool,
+    pub(crate) dialect: Dialect,
+    pub(crate) limits: Limits,
+    pub(crate) buffer_capacity: usize,
+    pub(crate) flexible: bool,
+    pub(crate) has_headers: bool,
+    pub(crate) trim: Trim,
+    pub(crate) skip_footer: usize,
 }
 
 impl Default for ReaderBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
+            limits: Limits::default(),
             buffer_capacity: 8192,
             flexible: false,
             has_headers: true,
+            trim: Trim::None,
+            skip_footer: 0,
         }
     }
 }
@@ -46,11 +63,27 @@ impl ReaderBuilder {
 
     /// Set the delimiter to be used by the created [`Reader`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`Reader`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -60,7 +93,124 @@ impl ReaderBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`Reader`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`Reader`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`Reader`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`Reader`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`Reader`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Indicate whether the created [`Reader`] should be strict regarding
+    /// quoting, i.e. whether it should return an error when finding a quote
+    /// in the middle of an unquoted field, or unexpected data after a closing
+    /// quote, instead of trying its best to make sense of it.
+    ///
+    /// A strict [`Reader`] will also return an error when reaching the end of
+    /// the stream in the middle of a quoted field, which typically happens
+    /// when reading truncated data. A non-strict one will yield the truncated
+    /// record as is, but [`Reader::ended_in_quote`] can be checked afterwards.
+    ///
+    /// Will default to `false`.
+    pub fn strict(&mut self, yes: bool) -> &mut Self {
+        self.dialect.strict = yes;
+        self
+    }
+
+    /// Set the maximum number of bytes a single record may span in the stream
+    /// read by the created [`Reader`]. Reading a larger record will return an
+    /// [`ErrorKind::RecordTooLarge`](crate::ErrorKind::RecordTooLarge) error
+    /// instead of buffering it indefinitely.
+    ///
+    /// Will default to no limit.
+    pub fn max_record_size(&mut self, size: usize) -> &mut Self {
+        self.limits.max_record_size = Some(size);
+        self
+    }
+
+    /// Set the maximum number of fields a single record may have when read
+    /// by the created [`Reader`]. Reading a record with more fields will return an
+    /// [`ErrorKind::TooManyFields`](crate::ErrorKind::TooManyFields) error.
+    ///
+    /// Will default to no limit.
+    pub fn max_fields(&mut self, fields: usize) -> &mut Self {
+        self.limits.max_fields = Some(fields);
+        self
+    }
+
+    /// Indicate whether the created [`Reader`] should skip empty lines.
+    ///
+    /// When set to `false`, empty lines will be yielded as records having a
+    /// single empty field. Note that unless the reader is also flexible, this
+    /// will fail when reading CSV data having more than a single column.
+    ///
+    /// Will default to `true`.
+    pub fn skip_empty_lines(&mut self, yes: bool) -> &mut Self {
+        self.dialect.skip_empty_lines = yes;
+        self
+    }
+
+    /// Indicate whether the created [`Reader`] should only consider quotes
+    /// found at the very beginning of a field as opening a quoted field, like
+    /// the `csv` crate does, instead of entering the quoted state on any
+    /// quote. This way, dirty data such as `joh"n` won't misalign records.
+    ///
+    /// This mode is slightly slower and makes [`Self::strict`] only report
+    /// unexpected data after closing quotes.
+    ///
+    /// Will default to `false`.
+    pub fn lenient_quotes(&mut self, yes: bool) -> &mut Self {
+        self.dialect.lenient_quotes = yes;
         self
     }
 
@@ -80,6 +230,34 @@ impl ReaderBuilder {
         self
     }
 
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`Reader`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`Reader::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`Reader`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
+        self
+    }
+
     /// Indicate whether first record must be understood as a header.
     ///
     /// Will default to `true`.
@@ -88,18 +266,122 @@ impl ReaderBuilder {
         self
     }
 
+    /// Set whether the created [`Reader`] should trim ASCII whitespace around
+    /// headers and/or fields. Quoted fields are only trimmed outside of
+    /// their quotes.
+    ///
+    /// Note that trimming fields is slower since records cannot be read in a
+    /// single pass anymore.
+    ///
+    /// Will default to [`Trim::None`].
+    pub fn trim(&mut self, trim: Trim) -> &mut Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Set a number of records to set aside at the end of the stream, e.g. to
+    /// discard some "TOTAL" footer row. Those records will not be checked
+    /// against the header row length and can be retrieved using
+    /// [`Reader::footer`] or [`ReverseReader::footer`].
+    ///
+    /// Since the stream must be read in reverse to find them, this option is
+    /// only taken into account by [`Self::from_seekable_reader`],
+    /// [`Self::resume_from_reader`] and [`Self::reverse_from_reader`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_footer(&mut self, count: usize) -> &mut Self {
+        self.skip_footer = count;
+        self
+    }
+
     /// Create a new [`Reader`] using the provided reader implementing
     /// [`std::io::Read`].
     pub fn from_reader<R: Read>(&self, reader: R) -> Reader<R> {
         Reader {
             buffer: BufReaderWithPosition::with_capacity(self.buffer_capacity, reader),
-            inner: CoreReader::new(self.delimiter, self.quote),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
             flexible: self.flexible,
             headers: ByteRecord::new(),
             has_read: false,
             must_reemit_headers: !self.has_headers,
             has_headers: self.has_headers,
-            index: 0,
+            trim: self.trim,
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
+            footer: Vec::new(),
+            data_end: None,
+        }
+    }
+
+    /// Create a new [`Reader`] using the provided reader implementing both
+    /// [`std::io::Read`] and [`std::io::Seek`], so that footer records can be
+    /// found beforehand by reading the end of the stream in reverse.
+    ///
+    /// See [`Self::skip_footer`] for more detail.
+    pub fn from_seekable_reader<R: Read + Seek>(&self, mut reader: R) -> error::Result<Reader<R>> {
+        let initial_pos = reader.stream_position()?;
+
+        if self.skip_footer == 0 {
+            return Ok(self.from_reader(reader));
+        }
+
+        let mut reverse_reader = self.reverse_from_reader(&mut reader)?;
+        let footer = std::mem::take(&mut reverse_reader.footer);
+        let data_end = reverse_reader.data_end() - initial_pos;
+
+        reader.seek(SeekFrom::Start(initial_pos))?;
+
+        let mut forward_reader = self.from_reader(reader);
+        forward_reader.footer = footer;
+        forward_reader.data_end = Some(data_end);
+
+        Ok(forward_reader)
+    }
+
+    /// Create a new [`Reader`] resuming reading where the one a [`Checkpoint`]
+    /// was taken from stopped, using the provided reader implementing both
+    /// [`std::io::Read`] and [`std::io::Seek`].
+    ///
+    /// The given reader must stand where the checkpointed one started, e.g.
+    /// at the beginning of the same file. The dialect recorded by the
+    /// checkpoint will be used instead of the one of this builder.
+    ///
+    /// Footer records are found beforehand, as with
+    /// [`Self::from_seekable_reader`]. See [`Self::skip_footer`] for more detail.
+    pub fn resume_from_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+        checkpoint: &Checkpoint,
+    ) -> error::Result<Reader<R>> {
+        let mut csv_reader = self.resumed(checkpoint).from_seekable_reader(reader)?;
+
+        csv_reader.inner.trim = self.trim.fields();
+        csv_reader.headers = checkpoint.byte_headers().clone();
+        csv_reader.must_reemit_headers = false;
+        csv_reader.has_read = true;
+
+        csv_reader.seek(checkpoint.position())?;
+
+        Ok(csv_reader)
+    }
+
+    // NOTE: the checkpoint records the dialect to use, save for the preamble
+    // lines to skip, which are still needed to find where footer records may
+    // start when reading in reverse.
+    fn resumed(&self, checkpoint: &Checkpoint) -> Self {
+        Self {
+            dialect: Dialect {
+                skip_lines: self.dialect.skip_lines.clone(),
+                ..checkpoint.dialect().clone()
+            },
+            limits: self.limits,
+            buffer_capacity: self.buffer_capacity,
+            flexible: self.flexible,
+            has_headers: checkpoint.has_headers(),
+            trim: self.trim,
+            skip_footer: self.skip_footer,
         }
     }
 
@@ -113,6 +395,7 @@ impl ReaderBuilder {
 
         let mut forward_reader = self.from_reader(reader);
         let headers = forward_reader.byte_headers()?.clone();
+        let preamble = std::mem::take(&mut forward_reader.preamble);
         let position_after_headers = forward_reader.position();
 
         let mut reader = forward_reader.into_inner();
@@ -122,17 +405,43 @@ impl ReaderBuilder {
         let offset = if self.has_headers {
             initial_pos + position_after_headers
         } else {
-            initial_pos
+            initial_pos + preamble.len() as u64
         };
 
         let reverse_io_reader = utils::ReverseReader::new(reader, file_len, offset);
 
-        Ok(ReverseReader {
-            buffer: BufReader::with_capacity(self.buffer_capacity, reverse_io_reader),
-            inner: CoreReader::new(self.delimiter, self.quote),
+        // NOTE: comment lines cannot be recognized when reading in reverse
+        // since their leading comment char would be found at the end. Escape
+        // chars are also ignored since they would follow the escaped byte.
+        // Field starts, required by lenient quotes & strict mode, cannot be
+        // known either, since the bytes preceding them are read afterwards.
+        // Finally, multi-byte delimiters must be searched backwards.
+        let mut dialect = self.dialect.clone();
+        dialect.comment = None;
+        dialect.escape = None;
+        dialect.lenient_quotes = false;
+        dialect.strict = false;
+        dialect.delimiter = dialect.delimiter.reversed();
+
+        let mut inner = CoreReader::new(&dialect);
+        inner.trim = self.trim.fields();
+
+        let mut reverse_reader = ReverseReader {
+            buffer: BufReaderWithPosition::with_capacity(self.buffer_capacity, reverse_io_reader),
+            inner,
             flexible: self.flexible,
             headers,
-        })
+            preamble,
+            footer: Vec::new(),
+            stream_len: file_len,
+            data_end: file_len,
+        };
+
+        if self.skip_footer > 0 {
+            reverse_reader.read_footer(self.skip_footer)?;
+        }
+
+        Ok(reverse_reader)
     }
 }
 
@@ -146,12 +455,20 @@ impl ReaderBuilder {
 pub struct Reader<R> {
     buffer: BufReaderWithPosition<R>,
     inner: CoreReader,
+    tracker: Tracker,
     flexible: bool,
     headers: ByteRecord,
     has_read: bool,
     must_reemit_headers: bool,
     has_headers: bool,
-    index: u64,
+    trim: Trim,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
+    footer: Vec<ByteRecord>,
+    // NOTE: position where footer records start, relative to the beginning
+    // of the stream
+    data_end: Option<u64>,
 }
 
 impl<R: Read> Reader<R> {
@@ -161,55 +478,35 @@ impl<R: Read> Reader<R> {
         ReaderBuilder::new().from_reader(reader)
     }
 
-    #[inline]
-    fn check_field_count(&mut self, byte: u64, written: usize) -> error::Result<()> {
-        if self.flexible {
-            return Ok(());
-        }
-
-        if self.has_read && written != self.headers.len() {
-            return Err(Error::new(ErrorKind::UnequalLengths {
-                expected_len: self.headers.len(),
-                len: written,
-                pos: Some((
-                    byte,
-                    self.index
-                        .saturating_sub(if self.has_headers { 1 } else { 0 }),
-                )),
-            }));
-        }
-
-        Ok(())
-    }
-
     fn read_byte_record_impl(&mut self, record: &mut ByteRecord) -> error::Result<bool> {
-        use ReadResult::*;
-
         record.clear();
 
         let mut record_builder = ByteRecordBuilder::wrap(record);
         let byte = self.position();
 
-        loop {
-            let input = self.buffer.fill_buf()?;
+        self.tracker.start(self.buffer.position());
 
-            let (result, pos) = self.inner.read_record(input, &mut record_builder);
+        if self.data_end.is_some_and(|end| byte >= end) {
+            return Ok(false);
+        }
 
-            self.buffer.consume(pos);
+        let expected_len = (!self.flexible && self.has_read).then(|| self.headers.len());
 
-            match result {
-                End => {
-                    return Ok(false);
-                }
-                Cr | Lf | InputEmpty => {
-                    continue;
-                }
-                Record => {
-                    self.index += 1;
-                    self.check_field_count(byte, record.len())?;
-                    return Ok(true);
-                }
-            };
+        loop {
+            let input = self.buffer.fill_buf()?;
+            let read = self.inner.read_record(input, &mut record_builder);
+
+            match self.tracker.read_step(
+                &mut self.buffer,
+                read,
+                &mut self.inner,
+                &mut record_builder,
+                expected_len,
+            )? {
+                Step::End => return Ok(false),
+                Step::Continue => continue,
+                Step::Record => return Ok(true),
+            }
         }
     }
 
@@ -223,12 +520,25 @@ impl<R: Read> Reader<R> {
         let input = self.buffer.fill_buf()?;
         let bom_len = trim_bom(input);
         self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        self.preamble = self.skip_lines.skip(&mut self.buffer, &self.inner)?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
 
         // Reading headers
         let mut headers = ByteRecord::new();
 
+        self.inner.trim = if self.has_headers {
+            self.trim.headers()
+        } else {
+            self.trim.fields()
+        };
+
         let has_data = self.read_byte_record_impl(&mut headers)?;
 
+        self.inner.trim = self.trim.fields();
+
         if !has_data {
             self.must_reemit_headers = false;
         }
@@ -246,6 +556,18 @@ impl<R: Read> Reader<R> {
         self.has_headers
     }
 
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
     /// Attempt to return a reference to this reader's first record.
     #[inline]
     pub fn byte_headers(&mut self) -> error::Result<&ByteRecord> {
@@ -254,6 +576,33 @@ impl<R: Read> Reader<R> {
         Ok(&self.headers)
     }
 
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    #[inline]
+    pub fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read()?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read()?;
+
+        Ok(self.had_bom)
+    }
+
+    /// Return the footer records that were set aside at the end of the stream,
+    /// in stream order.
+    ///
+    /// See [`ReaderBuilder::skip_footer`] for more detail.
+    #[inline]
+    pub fn footer(&self) -> &[ByteRecord] {
+        &self.footer
+    }
+
     /// Attempt to select the desired columns.
     pub fn select(&mut self, selector: &Selector) -> error::Result<Selection> {
         let has_headers = self.has_headers;
@@ -332,6 +681,78 @@ impl<R: Read> Reader<R> {
         }
     }
 
+    /// Return an iterator deserializing records into any type implementing
+    /// [`serde::Deserialize`].
+    ///
+    /// If the reader was configured to have headers, struct fields will be
+    /// mapped to the columns having the same name, else they will be mapped
+    /// by position. See [`ByteRecord::deserialize`] for more detail.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<D: DeserializeOwned>(&mut self) -> DeserializeRecordsIter<'_, R, D> {
+        DeserializeRecordsIter {
+            reader: self,
+            record: ByteRecord::new(),
+            headers: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Transform the reader into an iterator deserializing records into any
+    /// type implementing [`serde::Deserialize`].
+    ///
+    /// See [`Self::deserialize`] for more detail.
+    #[cfg(feature = "serde")]
+    pub fn into_deserialize<D: DeserializeOwned>(self) -> DeserializeRecordsIntoIter<R, D> {
+        DeserializeRecordsIntoIter {
+            reader: self,
+            record: ByteRecord::new(),
+            headers: None,
+            _marker: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn read_deserialized<D: DeserializeOwned>(
+        &mut self,
+        headers: &mut Option<ByteHeadersIndex>,
+        record: &mut ByteRecord,
+    ) -> error::Result<Option<D>> {
+        // NOTE: headers are indexed only once, on first read
+        if self.has_headers && headers.is_none() {
+            *headers = Some(ByteHeadersIndex::new(self.byte_headers()?.clone(), true));
+        }
+
+        if !self.read_byte_record(record)? {
+            return Ok(None);
+        }
+
+        match headers {
+            Some(index) => record.deserialize_with_index(index),
+            None => record.deserialize(None),
+        }
+        .map(Some)
+    }
+
+    /// Attempt to take a [`Checkpoint`] recording the position of the next
+    /// record to read, so that reading can later be resumed using
+    /// [`ReaderBuilder::resume_from_reader`].
+    pub fn checkpoint(&mut self) -> error::Result<Checkpoint> {
+        self.on_first_read()?;
+
+        let position = if self.must_reemit_headers {
+            self.headers.position().copied().unwrap_or_default()
+        } else {
+            self.tracker.position_at(self.buffer.position())
+        };
+
+        Ok(Checkpoint::new(
+            position,
+            self.headers.clone(),
+            self.has_headers,
+            self.inner.dialect(),
+        ))
+    }
+
     /// Get an immutable reference to the underlying reader.
     pub fn get_ref(&self) -> &R {
         self.buffer.get_ref()
@@ -352,14 +773,38 @@ impl<R: Read> Reader<R> {
     /// Returns the current byte offset of the reader in the wrapped stream.
     #[inline(always)]
     pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
         if self.must_reemit_headers {
-            0
+            self.tracker.record_position().byte
         } else {
             self.buffer.position()
         }
     }
 }
 
+impl<R: Read + Seek> Reader<R> {
+    /// Seek the underlying stream to the given [`Position`], typically taken
+    /// from a previously read record, so that the next record read will be
+    /// the one starting there.
+    ///
+    /// Buffered data is discarded, but already read headers are kept and
+    /// records will continue to be numbered from `pos.record`.
+    ///
+    /// **BEWARE**: `pos.byte` is relative to the beginning of the stream as
+    /// reported by [`Self::position`], and must be the exact beginning of a
+    /// CSV record, or yielded records will therefore be incorrect.
+    pub fn seek(&mut self, pos: Position) -> error::Result<()> {
+        self.on_first_read()?;
+
+        self.buffer.seek(pos.byte)?;
+        self.inner.reset();
+        self.must_reemit_headers = false;
+        self.tracker.seek(pos);
+
+        Ok(())
+    }
+}
+
 pub struct ByteRecordsIter<'r, R> {
     reader: &'r mut Reader<R>,
     record: ByteRecord,
@@ -444,6 +889,46 @@ impl<R: Read> Iterator for StringRecordsIntoIter<R> {
     }
 }
 
+#[cfg(feature = "serde")]
+pub struct DeserializeRecordsIter<'r, R, D> {
+    reader: &'r mut Reader<R>,
+    record: ByteRecord,
+    headers: Option<ByteHeadersIndex>,
+    _marker: PhantomData<D>,
+}
+
+#[cfg(feature = "serde")]
+impl<R: Read, D: DeserializeOwned> Iterator for DeserializeRecordsIter<'_, R, D> {
+    type Item = error::Result<D>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader
+            .read_deserialized(&mut self.headers, &mut self.record)
+            .transpose()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub struct DeserializeRecordsIntoIter<R, D> {
+    reader: Reader<R>,
+    record: ByteRecord,
+    headers: Option<ByteHeadersIndex>,
+    _marker: PhantomData<D>,
+}
+
+#[cfg(feature = "serde")]
+impl<R: Read, D: DeserializeOwned> Iterator for DeserializeRecordsIntoIter<R, D> {
+    type Item = error::Result<D>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader
+            .read_deserialized(&mut self.headers, &mut self.record)
+            .transpose()
+    }
+}
+
 /// An already configured reverse CSV reader.
 ///
 /// # Configuration
@@ -451,11 +936,23 @@ impl<R: Read> Iterator for StringRecordsIntoIter<R> {
 /// To configure a [`ReverseReader`], if you need a custom delimiter for instance of if
 /// you want to tweak the size of the inner buffer. Check out the
 /// [`ReaderBuilder`].
+///
+/// Note that escape chars (see [`ReaderBuilder::escape`]), lenient quotes
+/// (see [`ReaderBuilder::lenient_quotes`]) and strict mode (see
+/// [`ReaderBuilder::strict`]) are not supported when reading in reverse and
+/// will be ignored.
 pub struct ReverseReader<R> {
     inner: CoreReader,
-    buffer: BufReader<utils::ReverseReader<R>>,
+    buffer: BufReaderWithPosition<utils::ReverseReader<R>>,
     flexible: bool,
     headers: ByteRecord,
+    preamble: Vec<u8>,
+    footer: Vec<ByteRecord>,
+    stream_len: u64,
+    // NOTE: absolute position where footer records start. Since reading in
+    // reverse also consumes the terminator of the preceding record, it
+    // actually falls within said terminator.
+    data_end: u64,
 }
 
 impl<R: Read + Seek> ReverseReader<R> {
@@ -471,6 +968,51 @@ impl<R: Read + Seek> ReverseReader<R> {
         &self.headers
     }
 
+    /// Return the raw preamble lines that were skipped before the header
+    /// row, terminators included.
+    pub fn preamble(&self) -> &[u8] {
+        &self.preamble
+    }
+
+    /// Return the footer records that were set aside at the end of the stream,
+    /// in stream order.
+    ///
+    /// See [`ReaderBuilder::skip_footer`] for more detail.
+    pub fn footer(&self) -> &[ByteRecord] {
+        &self.footer
+    }
+
+    #[inline(always)]
+    pub(crate) fn data_end(&self) -> u64 {
+        self.data_end
+    }
+
+    fn read_footer(&mut self, count: usize) -> error::Result<()> {
+        // NOTE: footer records are not expected to align with the headers
+        let flexible = self.flexible;
+        self.flexible = true;
+
+        let mut record = ByteRecord::new();
+        let mut result = Ok(());
+
+        while self.footer.len() < count {
+            match self.read_byte_record(&mut record) {
+                Ok(true) => self.footer.push(record.clone()),
+                Ok(false) => break,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        self.flexible = flexible;
+        self.footer.reverse();
+        self.data_end = self.stream_len - self.buffer.position();
+
+        result
+    }
+
     #[inline]
     fn check_field_count(&mut self, written: usize) -> error::Result<()> {
         if self.flexible {
@@ -510,9 +1052,15 @@ impl<R: Read + Seek> ReverseReader<R> {
                 End => {
                     return Ok(false);
                 }
-                Cr | Lf | InputEmpty => {
+                Cr | Lf | Comment | InputEmpty => {
                     continue;
                 }
+                MalformedQuote => {
+                    return Err(Error::new(ErrorKind::MalformedQuote { pos: None }));
+                }
+                UnterminatedQuote => {
+                    return Err(Error::new(ErrorKind::UnterminatedQuote { pos: None }));
+                }
                 Record => {
                     self.check_field_count(record.len())?;
                     record.reverse();
@@ -584,6 +1132,9 @@ mod tests {
     use std::io::Cursor;
 
     use super::*;
+    use crate::dialect::Terminator;
+    use crate::select::ByteHeadersIndex;
+    use crate::writer::WriterBuilder;
 
     impl<R: Read> Reader<R> {
         fn from_reader_no_headers(reader: R) -> Self {
@@ -652,6 +1203,8 @@ mod tests {
             brec!["name", "surname", "age"]
         );
 
+        assert!(!reader.input_had_bom()?);
+
         let mut reader =
             Reader::from_reader_no_headers(Cursor::new(b"\xef\xbb\xbfname,surname,age"));
 
@@ -659,27 +1212,63 @@ mod tests {
             reader.byte_records().next().unwrap()?,
             brec!["name", "surname", "age"]
         );
+        assert!(reader.input_had_bom()?);
 
         Ok(())
     }
 
     #[test]
-    fn test_empty_row() -> error::Result<()> {
-        let data = "name\n\"\"\nlucy\n\"\"";
-
-        // Read
-        let reader = Reader::from_reader_no_headers(Cursor::new(data));
+    fn test_decoding_reader() -> error::Result<()> {
+        use crate::encoding::{DecodingReader, Encoding};
 
-        let expected = vec![brec!["name"], brec![""], brec!["lucy"], brec![""]];
+        let utf16: Vec<u8> = "\u{FEFF}name,city\r\nÉloïse,\"Zürich\"\r\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
 
-        let records = reader.into_byte_records().collect::<Result<Vec<_>, _>>()?;
+        // NOTE: the BOM can only be trimmed if it fits in the buffer
+        for capacity in [32usize, 4, 3] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .from_reader(DecodingReader::new(utf16.as_slice(), Encoding::Latin1));
 
-        assert_eq!(records, expected);
+            assert!(reader.input_had_bom()?);
+            assert_eq!(reader.byte_headers()?, &brec!["name", "city"]);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["Éloïse", "Zürich"]]
+            );
 
-        Ok(())
-    }
+            let mut reader = ReaderBuilder::with_capacity(capacity).from_reader(
+                DecodingReader::new(&b"name\n\x93caf\xe9\x94\n"[..], Encoding::Windows1252),
+            );
 
-    #[test]
+            assert!(!reader.input_had_bom()?);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["“café”"]]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_row() -> error::Result<()> {
+        let data = "name\n\"\"\nlucy\n\"\"";
+
+        // Read
+        let reader = Reader::from_reader_no_headers(Cursor::new(data));
+
+        let expected = vec![brec!["name"], brec![""], brec!["lucy"], brec![""]];
+
+        let records = reader.into_byte_records().collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(records, expected);
+
+        Ok(())
+    }
+
+    #[test]
     fn test_crlf() -> error::Result<()> {
         let reader = Reader::from_reader_no_headers(Cursor::new(
             "name,surname\r\nlucy,\"john\"\r\nevan,zhong\r\nbéatrice,glougou\r\n",
@@ -699,6 +1288,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_terminator() -> error::Result<()> {
+        let tests = [
+            (
+                Terminator::Any(b'\r'),
+                "name,surname\rjohn,\"lan\rdis\"\r\rlucy,rose\nwhatever\r",
+                vec![
+                    brec!["name", "surname"],
+                    brec!["john", "lan\rdis"],
+                    brec!["lucy", "rose\nwhatever"],
+                ],
+            ),
+            (
+                Terminator::Any(b'\x1e'),
+                "name,surname\x1ejohn,\"lan\x1edis\"\x1e\x1elucy,rose\r\nwhatever",
+                vec![
+                    brec!["name", "surname"],
+                    brec!["john", "lan\x1edis"],
+                    brec!["lucy", "rose\r\nwhatever"],
+                ],
+            ),
+        ];
+
+        for (terminator, data, expected) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .has_headers(false)
+                    .terminator(terminator)
+                    .from_reader(Cursor::new(data));
+
+                assert_eq!(
+                    reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                    expected
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_quote_always() -> error::Result<()> {
         let reader = Reader::from_reader_no_headers(Cursor::new(
@@ -915,6 +1544,1235 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_comment() -> error::Result<()> {
+        let csv = "# exported on 2024-01-01\nname,surname\n#john,landis\nlucy,\"#rose\"\n# \"unbalanced\r\n\n#\nbeatrice,babka\n# trailing";
+
+        let expected = vec![
+            brec!["name", "surname"],
+            brec!["lucy", "#rose"],
+            brec!["beatrice", "babka"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(csv));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected,
+            );
+        }
+
+        // Comment char should be kept as data when not configured
+        let mut reader = Reader::from_reader_no_headers(Cursor::new("#name\n#john"));
+
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["#name"], brec!["#john"]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict() -> error::Result<()> {
+        let valid = "name,surname\r\n\"jo\"\"hn\",\"lan,dis\"\r\nlucy,\"\"\n\"\",rose";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .strict(true)
+                .from_reader(Cursor::new(valid));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    brec!["jo\"hn", "lan,dis"],
+                    brec!["lucy", ""],
+                    brec!["", "rose"]
+                ]
+            );
+        }
+
+        let tests = [
+            ("name,surname\njoh\"n,landis\nlucy,rose", (16, 1)),
+            ("name,surname\n\"john\"ny,landis\nlucy,rose", (19, 1)),
+            ("name,surname\n\"john\",landis\nlucy,ro\"se\"", (34, 2)),
+        ];
+
+        for (data, expected_pos) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .from_reader(Cursor::new(data));
+
+                let err = reader
+                    .byte_records()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == expected_pos
+                ));
+            }
+
+            // Default should remain lenient
+            let mut reader = ReaderBuilder::new()
+                .flexible(true)
+                .from_reader(Cursor::new(data));
+            assert!(reader.byte_records().all(|result| result.is_ok()));
+        }
+
+        // Reading must resume with the record following the malformed one
+        let tests = [
+            ("a,b\nc,d\"e\nf,g\n", (7, 1), brec!["f", "g"]),
+            ("a,b\n\"c\nd\"e,f\ng,h\n", (9, 1), brec!["g", "h"]),
+        ];
+
+        for (data, expected_pos, expected_record) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .has_headers(false)
+                    .from_reader(Cursor::new(data));
+
+                let mut record = ByteRecord::new();
+
+                assert!(reader.read_byte_record(&mut record)?);
+
+                let err = reader.read_byte_record(&mut record).unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == expected_pos
+                ));
+
+                assert!(reader.read_byte_record(&mut record)?);
+                assert_eq!(record, expected_record);
+                assert_eq!(record.position().map(|pos| pos.record), Some(2));
+                assert!(!reader.read_byte_record(&mut record)?);
+            }
+        }
+
+        // The whole delimiter, or terminator, must follow a closing quote
+        let valid = "\"a\"||b\n\"c\"\r\nd||\"e\"\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .strict(true)
+                .flexible(true)
+                .has_headers(false)
+                .delimiter_bytes(b"||")
+                .from_reader(Cursor::new(valid));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["a", "b"], brec!["c"], brec!["d", "e"]]
+            );
+        }
+
+        let tests = [
+            (
+                b"||".as_slice(),
+                "\"a\"|x||b\nc||d\n",
+                (4, 0),
+                Some(brec!["c", "d"]),
+            ),
+            (
+                b"||".as_slice(),
+                "\"a\"|\nc||d\n",
+                (4, 0),
+                Some(brec!["c", "d"]),
+            ),
+            (
+                b",".as_slice(),
+                "\"a\"\rb,c\nd,e\n",
+                (4, 0),
+                Some(brec!["d", "e"]),
+            ),
+            (
+                b"||".as_slice(),
+                "\"a\"\rb\nd||e\n",
+                (4, 0),
+                Some(brec!["d", "e"]),
+            ),
+            (b"||".as_slice(), "\"a\"|", (4, 0), None),
+        ];
+
+        for (delimiter, data, expected_pos, expected_record) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .has_headers(false)
+                    .delimiter_bytes(delimiter)
+                    .from_reader(Cursor::new(data));
+
+                let mut record = ByteRecord::new();
+
+                let err = reader.read_byte_record(&mut record).unwrap_err();
+
+                assert!(
+                    matches!(
+                        err.kind(),
+                        ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == expected_pos
+                    ),
+                    "data={:?} capacity={}",
+                    data,
+                    capacity
+                );
+
+                if let Some(expected_record) = &expected_record {
+                    assert!(reader.read_byte_record(&mut record)?);
+                    assert_eq!(&record, expected_record);
+                }
+
+                assert!(!reader.read_byte_record(&mut record)?);
+            }
+        }
+
+        // A quote following a closing quote is not escaped when quotes are
+        // not doubled
+        for delimiter in [b",".as_slice(), b"||"] {
+            let data = "\"a\"\"b\",c\nd,e\n".replace(',', core::str::from_utf8(delimiter).unwrap());
+
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .has_headers(false)
+                    .delimiter_bytes(delimiter)
+                    .double_quote(false)
+                    .from_reader(Cursor::new(&data));
+
+                let mut record = ByteRecord::new();
+
+                let err = reader.read_byte_record(&mut record).unwrap_err();
+
+                assert!(
+                    matches!(
+                        err.kind(),
+                        ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == (3, 0)
+                    ),
+                    "{:?}",
+                    err
+                );
+
+                assert!(reader.read_byte_record(&mut record)?);
+                assert_eq!(record, brec!["d", "e"]);
+                assert!(!reader.read_byte_record(&mut record)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unequal_lengths() -> error::Result<()> {
+        let data = "name,surname\njohn,landis\nlucy\n";
+
+        for has_headers in [true, false] {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .has_headers(has_headers)
+                    .from_reader(Cursor::new(data));
+
+                let err = reader
+                    .byte_records()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::UnequalLengths {
+                        expected_len: 2,
+                        len: 1,
+                        pos: Some((25, 2))
+                    }
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unterminated_quote() -> error::Result<()> {
+        use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+        let data = "name,surname\njohn,landis\nlucy,\"ro\nse";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .strict(true)
+                .from_reader(Cursor::new(data));
+
+            let mut record = ByteRecord::new();
+
+            assert!(reader.read_byte_record(&mut record)?);
+
+            let err = reader.read_byte_record(&mut record).unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::UnterminatedQuote { pos: Some((25, 2)) }
+            ));
+
+            assert!(!reader.read_byte_record(&mut record)?);
+            assert!(reader.ended_in_quote());
+        }
+
+        // Properly closed quotes at the end of the stream are fine
+        let mut reader = ReaderBuilder::new()
+            .strict(true)
+            .from_reader(Cursor::new("name\n\"john\""));
+
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["john"]]
+        );
+        assert!(!reader.ended_in_quote());
+
+        // Default should remain lenient, but still remember it
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity).from_reader(Cursor::new(data));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "landis"], brec!["lucy", "ro\nse"]]
+            );
+            assert!(reader.ended_in_quote());
+        }
+
+        // The newlines ending an unterminated quoted field are part of it, for
+        // every reader
+        let tests = [
+            ("\"ab\n", brec!["ab\n"]),
+            ("\"ab\r\n", brec!["ab\r\n"]),
+            ("a,\"b\n\n", brec!["a", "b\n\n"]),
+        ];
+
+        for (data, expected) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                for (terminator, delimiter) in [
+                    (Terminator::Any(b'\n'), b",".as_slice()),
+                    (Terminator::CRLF, b","),
+                    (Terminator::Any(b'\n'), b"||"),
+                ] {
+                    let data = data.replace(',', core::str::from_utf8(delimiter).unwrap());
+
+                    let mut reader = ReaderBuilder::with_capacity(capacity)
+                        .has_headers(false)
+                        .terminator(terminator)
+                        .delimiter_bytes(delimiter)
+                        .from_reader(Cursor::new(&data));
+
+                    assert_eq!(
+                        reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                        vec![expected.clone()]
+                    );
+                    assert!(reader.ended_in_quote());
+
+                    let mut zero_copy_reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                        .has_headers(false)
+                        .terminator(terminator)
+                        .delimiter_bytes(delimiter)
+                        .from_reader(Cursor::new(&data));
+
+                    assert_eq!(
+                        zero_copy_reader
+                            .read_byte_record()?
+                            .map(|record| record.to_byte_record()),
+                        Some(expected.clone())
+                    );
+                    assert!(zero_copy_reader.read_byte_record()?.is_none());
+                    assert!(zero_copy_reader.ended_in_quote());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() -> error::Result<()> {
+        let data = format!(
+            "name,surname\njohn,\"landis\nlucy,rose\n{}",
+            "a,b\n".repeat(32)
+        );
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .max_record_size(64)
+                .from_reader(Cursor::new(&data));
+
+            let err = reader
+                .byte_records()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::RecordTooLarge {
+                    pos: Some((13, 1)),
+                    limit: 64
+                }
+            ));
+        }
+
+        let data = "name,surname\njohn,landis\nlucy,rose,a,b,c,d\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .flexible(true)
+                .max_fields(3)
+                .from_reader(Cursor::new(data));
+
+            let mut record = ByteRecord::new();
+
+            assert!(reader.read_byte_record(&mut record)?);
+
+            let err = reader.read_byte_record(&mut record).unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::TooManyFields {
+                    pos: Some((25, 2)),
+                    limit: 3
+                }
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits_after_skipped_lines() -> error::Result<()> {
+        let comment = format!("#{}\n", "x".repeat(63));
+        let data = format!(
+            "{comment}\n{comment}name,surname\njohn,landis\n{comment}lucy,\"rose\n{}",
+            "a,b\n".repeat(20)
+        );
+
+        for has_headers in [true, false] {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .comment(Some(b'#'))
+                    .has_headers(has_headers)
+                    .max_record_size(32)
+                    .from_reader(Cursor::new(&data));
+
+                let err = reader
+                    .byte_records()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::RecordTooLarge {
+                        pos: Some((221, 2)),
+                        limit: 32
+                    }
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trim() -> error::Result<()> {
+        let data = " name , \" sur name \"\t\njohn,  \"la\"\"ndis\"  \n lucy ,rose \n";
+
+        let trimmed_headers = brec!["name", " sur name "];
+        let trimmed_records = vec![brec!["john", "la\"ndis"], brec!["lucy", "rose"]];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let read = |trim: Trim| -> error::Result<(ByteRecord, Vec<ByteRecord>)> {
+                let mut reader = ReaderBuilder::with_capacity(capacity)
+                    .trim(trim)
+                    .from_reader(Cursor::new(data));
+
+                Ok((
+                    reader.byte_headers()?.clone(),
+                    reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                ))
+            };
+
+            let (headers, records) = read(Trim::None)?;
+
+            assert_eq!(read(Trim::Headers)?, (trimmed_headers.clone(), records));
+            assert_eq!(read(Trim::Fields)?, (headers, trimmed_records.clone()));
+            assert_eq!(
+                read(Trim::All)?,
+                (trimmed_headers.clone(), trimmed_records.clone())
+            );
+        }
+
+        // Headers index
+        let mut reader = ReaderBuilder::new()
+            .trim(Trim::Headers)
+            .from_reader(Cursor::new(data));
+
+        let index = ByteHeadersIndex::new(reader.byte_headers()?.clone(), true);
+        assert_eq!(index.first_column_index_by_name(b" sur name "), Some(1));
+
+        // Reverse reading
+        let mut reader = ReaderBuilder::new()
+            .trim(Trim::All)
+            .reverse_from_reader(Cursor::new(data))?;
+
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["lucy", "rose"], brec!["john", "la\"ndis"]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoting() -> error::Result<()> {
+        let data = "name\tquote\njohn\t\"hello\n\"lucy\"\tok\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .delimiter(b'\t')
+                .quoting(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "quote"]);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "\"hello"], brec!["\"lucy\"", "ok\""]]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_quotes() -> error::Result<()> {
+        fn csv_crate_records(data: &str) -> Vec<ByteRecord> {
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(data.as_bytes())
+                .byte_records()
+                .map(|record| {
+                    let mut converted = ByteRecord::new();
+
+                    for cell in record.unwrap().iter() {
+                        converted.push_field(cell);
+                    }
+
+                    converted
+                })
+                .collect()
+        }
+
+        let data = "name,surname\njoh\"n,landis\nbéatrice,babka\n\"quoted, ok\",5\"\nfoo,\"bar\"\"\"\n5'11\",x\"y\n\"ab\"cd,\"x\"y\"z\"\n\"a\",\n";
+        let expected = csv_crate_records(data);
+
+        assert_eq!(expected[1], brec!["joh\"n", "landis"]);
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected
+            );
+        }
+
+        // Only a full multi-byte delimiter can precede a field start
+        let data = "name||notes||extra\njohn||a|\"b||c\"d\n\"lucy\"||\"x||y\"||z\n";
+        let expected = vec![
+            brec!["name", "notes", "extra"],
+            brec!["john", "a|\"b", "c\"d"],
+            brec!["lucy", "x||y", "z"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .delimiter_bytes(b"||")
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_empty_lines() -> error::Result<()> {
+        let data = "name\njohn\n\nlucy\r\n\r\n\"\"\n\nbeatrice\n";
+
+        let expected = vec![
+            brec!["john"],
+            brec![""],
+            brec!["lucy"],
+            brec![""],
+            brec![""],
+            brec![""],
+            brec!["beatrice"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_empty_lines(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_byte_delimiter() -> error::Result<()> {
+        let csv = "name||surname\njohn||\"la||ndis\"\nlucy|||rose\n\"a|\"||b|\n";
+
+        let expected = vec![
+            brec!["name", "surname"],
+            brec!["john", "la||ndis"],
+            brec!["lucy", "|rose"],
+            brec!["a|", "b|"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .delimiter_bytes(b"||")
+                .from_reader(Cursor::new(csv));
+
+            let records = reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(records, expected);
+
+            // Round-tripping through the writer
+            let mut writer = WriterBuilder::new()
+                .delimiter_bytes(b"||")
+                .from_writer(Vec::new());
+
+            for record in records.iter() {
+                writer.write_byte_record(record)?;
+            }
+
+            let written = writer.into_inner().unwrap();
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .delimiter_bytes(b"||")
+                .from_reader(Cursor::new(written));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected
+            );
+        }
+
+        // Non-ASCII delimiter, in reverse
+        let csv = "name¦surname\njohn¦\"lan¦dis\"\nlucy¦rose";
+
+        let mut reader = ReaderBuilder::new()
+            .delimiter_bytes("¦".as_bytes())
+            .reverse_from_reader(Cursor::new(csv))?;
+
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["lucy", "rose"], brec!["john", "lan¦dis"]]
+        );
+
+        assert_eq!(reader.byte_headers(), &brec!["name", "surname"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape() -> error::Result<()> {
+        let csv = "name,quote\njohn,\"he said \\\"hello\\\", then \\\\ left\"\n\"lucy \\\"the\\\\\",\"\\\",\\\n\"\n";
+
+        let expected = vec![
+            brec!["name", "quote"],
+            brec!["john", "he said \"hello\", then \\ left"],
+            brec!["lucy \"the\\", "\",\n"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .escape(Some(b'\\'))
+                .double_quote(false)
+                .from_reader(Cursor::new(csv));
+
+            let records = reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(records, expected);
+
+            // Round-tripping through the writer
+            let mut writer = WriterBuilder::new()
+                .escape(Some(b'\\'))
+                .double_quote(false)
+                .from_writer(Vec::new());
+
+            for record in records.iter() {
+                writer.write_byte_record(record)?;
+            }
+
+            let written = writer.into_inner().unwrap();
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .escape(Some(b'\\'))
+                .double_quote(false)
+                .from_reader(Cursor::new(written));
+
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                expected
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_quote_disabled() -> error::Result<()> {
+        use crate::splitter::SplitterBuilder;
+        use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+        let data = "\"x\"\"y\",z\n\"a\"\"\"\n\"b\"\"c\"\"d\",e\n\"f\"\"g\"h,i\nj,\"k\"\n\"a\"\"b,c\nx,y\n\"l\"\"m\nn,\"o\"\n\"p\"\"q\",\"r\"\"s\"\n";
+
+        // NOTE: quotes found in the middle of a field that was not quoted, like
+        // in `c"`, only are literal in lenient mode
+        let mid_field_data = "\"a\"\"b,c\"\nx,y\n";
+
+        // NOTE: a quote that is not doubled closes the quoted part of a field,
+        // as per the csv crate, and what follows it is literal until the field
+        // ends, be it with a delimiter or a newline
+        let read_expected = |data: &str| {
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .double_quote(false)
+                .from_reader(data.as_bytes())
+                .byte_records()
+                .map(|record| {
+                    let mut converted = ByteRecord::new();
+
+                    for cell in record.unwrap().iter() {
+                        converted.push_field(cell);
+                    }
+
+                    converted
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let expected = read_expected(data);
+
+        assert_eq!(expected[0], brec!["x\"y\"", "z"]);
+        assert_eq!(expected[5], brec!["a\"b", "c"]);
+        assert_eq!(expected[6], brec!["x", "y"]);
+        assert_eq!(expected[7], brec!["l\"m"]);
+        assert_eq!(expected[8], brec!["n", "o"]);
+        assert_eq!(expected[9], brec!["p\"q\"", "r\"s\""]);
+        assert_eq!(
+            read_expected(mid_field_data),
+            vec![brec!["a\"b", "c\""], brec!["x", "y"]]
+        );
+
+        let assert_same_fields = |records: &[ByteRecord], expected: &[ByteRecord]| {
+            assert_eq!(records.len(), expected.len());
+
+            for (i, (record, expected_record)) in records.iter().zip(expected.iter()).enumerate() {
+                assert_eq!(record.len(), expected_record.len(), "record={}", i);
+
+                for (j, (cell, expected_cell)) in
+                    record.iter().zip(expected_record.iter()).enumerate()
+                {
+                    assert_eq!(cell, expected_cell, "record={}, field={}", i, j);
+                }
+            }
+        };
+
+        for (data, lenient) in [(data, false), (data, true), (mid_field_data, true)] {
+            let expected = read_expected(data);
+            let multi_byte_data = data.replace(',', "||");
+
+            for capacity in [32usize, 4, 3, 2, 1] {
+                for (data, delimiter) in [(data, b",".as_slice()), (&multi_byte_data, b"||")] {
+                    let mut reader = ReaderBuilder::with_capacity(capacity)
+                        .has_headers(false)
+                        .flexible(true)
+                        .delimiter_bytes(delimiter)
+                        .double_quote(false)
+                        .lenient_quotes(lenient)
+                        .from_reader(Cursor::new(data));
+
+                    assert_same_fields(
+                        &reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                        &expected,
+                    );
+
+                    let mut zero_copy_reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                        .has_headers(false)
+                        .flexible(true)
+                        .delimiter_bytes(delimiter)
+                        .double_quote(false)
+                        .lenient_quotes(lenient)
+                        .from_reader(Cursor::new(data));
+
+                    let mut records = Vec::new();
+
+                    while let Some(record) = zero_copy_reader.read_byte_record()? {
+                        records.push(record.to_byte_record());
+                    }
+
+                    assert_same_fields(&records, &expected);
+
+                    let mut splitter = SplitterBuilder::with_capacity(capacity)
+                        .has_headers(false)
+                        .delimiter_bytes(delimiter)
+                        .double_quote(false)
+                        .lenient_quotes(lenient)
+                        .from_reader(Cursor::new(data));
+
+                    assert_eq!(splitter.count_records()?, expected.len() as u64);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_lines() -> error::Result<()> {
+        let data = "title: \"unbalanced\nsep=,\r\nname,surname\njohn,landis\nlucy,rose\n";
+        let preamble = b"title: \"unbalanced\nsep=,\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            // Count
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines(2)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.preamble()?, preamble);
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+            assert_eq!(reader.position(), 39);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "landis"], brec!["lucy", "rose"]]
+            );
+
+            // Predicate
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines_while(|line| !line.starts_with(b"name"))
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.preamble()?, preamble);
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+            assert_eq!(reader.position(), 39);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "landis"], brec!["lucy", "rose"]]
+            );
+
+            // No headers
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .skip_lines(2)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.preamble()?, preamble);
+            assert_eq!(reader.position(), 26);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    brec!["name", "surname"],
+                    brec!["john", "landis"],
+                    brec!["lucy", "rose"]
+                ]
+            );
+
+            // Skipping everything
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines(10)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.preamble()?, data.as_bytes());
+            assert!(reader.byte_headers()?.is_empty());
+            assert_eq!(reader.byte_records().count(), 0);
+        }
+
+        // Reverse reader
+        let mut reader = ReaderBuilder::new()
+            .skip_lines(2)
+            .reverse_from_reader(Cursor::new(data))?;
+
+        assert_eq!(reader.preamble(), preamble);
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["lucy", "rose"], brec!["john", "landis"]]
+        );
+
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .skip_lines(2)
+            .reverse_from_reader(Cursor::new(data))?;
+
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![
+                brec!["lucy", "rose"],
+                brec!["john", "landis"],
+                brec!["name", "surname"]
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_footer() -> error::Result<()> {
+        use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+        let data = "name,count\njohn,1\nlucy,\"2\n\"\n\n\"TOTAL\nRECORDS\"\r\nEOF,2,ok";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_footer(2)
+                .from_seekable_reader(Cursor::new(data))?;
+
+            assert_eq!(
+                reader.footer(),
+                &[brec!["TOTAL\nRECORDS"], brec!["EOF", "2", "ok"]]
+            );
+            assert_eq!(reader.byte_headers()?, &brec!["name", "count"]);
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "1"], brec!["lucy", "2\n"]]
+            );
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_footer(2)
+                .reverse_from_reader(Cursor::new(data))?;
+
+            assert_eq!(
+                reader.footer(),
+                &[brec!["TOTAL\nRECORDS"], brec!["EOF", "2", "ok"]]
+            );
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["lucy", "2\n"], brec!["john", "1"]]
+            );
+
+            // Field starts cannot be known when reading in reverse
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .lenient_quotes(true)
+                .skip_footer(2)
+                .from_seekable_reader(Cursor::new(data))?;
+
+            assert_eq!(
+                reader.footer(),
+                &[brec!["TOTAL\nRECORDS"], brec!["EOF", "2", "ok"]]
+            );
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "1"], brec!["lucy", "2\n"]]
+            );
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .strict(true)
+                .skip_footer(2)
+                .from_seekable_reader(Cursor::new(data))?;
+
+            assert_eq!(
+                reader.footer(),
+                &[brec!["TOTAL\nRECORDS"], brec!["EOF", "2", "ok"]]
+            );
+            assert_eq!(
+                reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["john", "1"], brec!["lucy", "2\n"]]
+            );
+        }
+
+        // Footer larger than data
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .skip_footer(10)
+            .from_seekable_reader(Cursor::new("name\njohn\n"))?;
+
+        assert_eq!(reader.footer(), &[brec!["name"], brec!["john"]]);
+        assert_eq!(reader.byte_records().count(), 0);
+
+        // Not starting at the beginning of the stream
+        let mut cursor = Cursor::new("metadata\nname\njohn\nTOTAL,1\n");
+        cursor.seek(SeekFrom::Start(9))?;
+
+        let mut reader = ReaderBuilder::new()
+            .skip_footer(1)
+            .from_seekable_reader(cursor)?;
+
+        assert_eq!(reader.footer(), &[brec!["TOTAL", "1"]]);
+        assert_eq!(
+            reader.byte_records().collect::<Result<Vec<_>, _>>()?,
+            vec![brec!["john"]]
+        );
+
+        // Resuming from a checkpoint
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_footer(2)
+                .from_seekable_reader(Cursor::new(data))?;
+
+            reader.byte_records().next().unwrap()?;
+
+            let checkpoint = reader.checkpoint()?;
+
+            let mut resumed = ReaderBuilder::with_capacity(capacity)
+                .skip_footer(2)
+                .resume_from_reader(Cursor::new(data), &checkpoint)?;
+
+            assert_eq!(resumed.footer(), reader.footer());
+            assert_eq!(
+                resumed.byte_records().collect::<Result<Vec<_>, _>>()?,
+                vec![brec!["lucy", "2\n"]]
+            );
+
+            let mut resumed = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .skip_footer(2)
+                .resume_from_reader(Cursor::new(data), &checkpoint)?;
+
+            assert_eq!(resumed.footer(), reader.footer());
+            assert_eq!(
+                resumed
+                    .read_byte_record()?
+                    .map(|record| record.to_byte_record()),
+                Some(brec!["lucy", "2\n"])
+            );
+            assert!(resumed.read_byte_record()?.is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_position() -> error::Result<()> {
+        let data = "sep=,\nname,notes\n\"john\",\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\nc\"\nlast,one";
+
+        let pos = |byte, line, record| Position { byte, line, record };
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .comment(Some(b'#'))
+                .from_reader(data.as_bytes());
+
+            assert_eq!(reader.byte_headers()?.position(), Some(&pos(6, 2, 0)));
+
+            let positions = reader
+                .byte_records()
+                .map(|result| result.map(|record| *record.position().unwrap()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(
+                positions,
+                vec![pos(17, 3, 1), pos(48, 7, 2), pos(59, 8, 3), pos(71, 11, 4)]
+            );
+
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_reader("john\n\"lucy\nrose\"\nbob".as_bytes());
+
+            let positions = reader
+                .byte_records()
+                .map(|result| result.map(|record| *record.position().unwrap()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(positions, vec![pos(0, 1, 0), pos(5, 2, 1), pos(17, 4, 2)]);
+
+            // Lines are counted using the configured terminator
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .skip_lines(1)
+                .terminator(Terminator::Any(b';'))
+                .from_reader("meta;a;\"b;c\";d\ne;f".as_bytes());
+
+            let positions = reader
+                .byte_records()
+                .map(|result| result.map(|record| *record.position().unwrap()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(
+                positions,
+                vec![pos(5, 2, 0), pos(7, 3, 1), pos(13, 5, 2), pos(17, 6, 3)]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_position_lines() -> error::Result<()> {
+        use crate::zero_copy_reader::ZeroCopyReaderBuilder;
+
+        let data = "a,b\n\"c\nd\",e\r\n\n# f\"\ng,\"h\"\"\ni\"\nj\"k,l\n\" m\n\" ,n\no,\"p\\\"\nq\"\n\"r\"\nlast";
+
+        // NOTE: a record's line is the number of newlines preceding it
+        let line_of = |byte: u64| 1 + count_lines(&data.as_bytes()[..byte as usize], b'\n');
+
+        macro_rules! configure {
+            ($builder:expr, $i:expr) => {{
+                let mut builder = $builder;
+                builder.has_headers(false).flexible(true);
+
+                match $i {
+                    0 => &mut builder,
+                    1 => builder.comment(Some(b'#')),
+                    2 => builder.lenient_quotes(true),
+                    3 => builder.trim(Trim::All),
+                    4 => builder.delimiter_bytes(b"||"),
+                    5 => builder.escape(Some(b'\\')).double_quote(false),
+                    _ => builder.quoting(false),
+                };
+
+                builder
+            }};
+        }
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            for i in 0..7 {
+                let mut reader = configure!(ReaderBuilder::with_capacity(capacity), i)
+                    .from_reader(data.as_bytes());
+
+                let mut zero_copy_reader =
+                    configure!(ZeroCopyReaderBuilder::with_capacity(capacity), i)
+                        .from_reader(data.as_bytes());
+
+                let mut count: usize = 0;
+
+                for result in reader.byte_records() {
+                    let pos = *result?.position().unwrap();
+                    let zero_copy_pos = *zero_copy_reader
+                        .read_byte_record()?
+                        .unwrap()
+                        .position()
+                        .unwrap();
+
+                    assert_eq!(pos.line, line_of(pos.byte), "capacity={} i={}", capacity, i);
+                    assert_eq!(pos, zero_copy_pos, "capacity={} i={}", capacity, i);
+
+                    count += 1;
+                }
+
+                assert!(count > 5);
+                assert!(zero_copy_reader.read_byte_record()?.is_none());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> error::Result<()> {
+        let data =
+            "name,notes\njohn,\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\"\nlast,one\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            let expected = reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            for record in expected.iter().rev() {
+                reader.seek(*record.position().unwrap())?;
+
+                let records = reader.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+                assert_eq!(&records[0], record);
+                assert_eq!(records[0].position(), record.position());
+                assert_eq!(records.last(), expected.last());
+            }
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "notes"]);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() -> error::Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Row {
+            age: Option<u32>,
+            name: String,
+        }
+
+        let data = "name,surname,age\n\"john\",\"landy, the \"\"everlasting\"\" bastard\",45\nlucy,rose,\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ReaderBuilder::with_capacity(capacity).from_reader(data.as_bytes());
+
+            assert_eq!(
+                reader.deserialize::<Row>().collect::<Result<Vec<_>, _>>()?,
+                vec![
+                    Row {
+                        age: Some(45),
+                        name: "john".to_string()
+                    },
+                    Row {
+                        age: None,
+                        name: "lucy".to_string()
+                    }
+                ]
+            );
+
+            let reader = ReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_reader(data.as_bytes());
+
+            let rows = reader
+                .into_deserialize::<(String, String, String)>()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(rows.len(), 3);
+            assert_eq!(rows[0].2, "age");
+            assert_eq!(rows[1].1, "landy, the \"everlasting\" bastard");
+        }
+
+        let mut reader = Reader::from_reader("name,age\njohn,old\n".as_bytes());
+
+        assert!(matches!(
+            reader
+                .deserialize::<Row>()
+                .next()
+                .unwrap()
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Deserialize {
+                pos: Some((9, 1)),
+                field: Some(1),
+                ..
+            }
+        ));
+
+        Ok(())
+    }
+
     // #[test]
     // fn test_fuzzing_failures() -> error::Result<()> {
     //     let data = [13u8, 13, 96, 34, 34, 44, 10, 44, 96, 34, 13, 34, 44, 10];
@@ -1,32 +1,72 @@
-use std::borrow::Cow;
-use std::fmt;
-use std::hash::{Hash, Hasher};
-use std::io::Write;
-use std::ops::Index;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Index;
 
 use crate::debug;
-use crate::utils::{trim_trailing_crlf, unescape, unescape_to, unquoted, AppendOnlyView};
-
+use crate::dialect::{Delimiter, Quoting, Terminator};
+use crate::position::Position;
+use crate::utils::{unescape_with, unescape_with_to, AppendOnlyView};
+
+#[cfg(feature = "serde")]
+use crate::deserializer::deserialize_record;
+#[cfg(any(feature = "str", feature = "serde"))]
+use crate::error;
 #[cfg(feature = "str")]
-use crate::error::{self, Error, ErrorKind};
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "serde")]
+use crate::select::ByteHeadersIndex;
 
 /// A view of a CSV record into a [`ZeroCopyReader`](crate::ZeroCopyReader) buffer.
 pub struct ZeroCopyByteRecord<'a> {
     slice: &'a [u8],
     seps: &'a [usize],
-    pub(crate) quote: u8,
+    pub(crate) delimiter: Delimiter,
+    // NOTE: only needed by writers, to know whether the record can be written
+    // as is
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) terminator: Terminator,
+    pub(crate) quoting: Quoting,
+    pub(crate) trim: bool,
+    position: Option<Position>,
 }
 
 impl<'a> ZeroCopyByteRecord<'a> {
     #[inline]
-    pub(crate) fn new(slice: &'a [u8], seps: &'a [usize], quote: u8) -> Self {
+    pub(crate) fn new(
+        slice: &'a [u8],
+        seps: &'a [usize],
+        delimiter: Delimiter,
+        terminator: Terminator,
+        quoting: Quoting,
+        trim: bool,
+    ) -> Self {
         Self {
-            slice: trim_trailing_crlf(slice),
+            slice,
             seps,
-            quote,
+            delimiter,
+            terminator,
+            quoting,
+            trim,
+            position: None,
         }
     }
 
+    #[inline(always)]
+    pub(crate) fn with_position(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Returns the position of the record in the stream it was read from, if
+    /// known.
+    #[inline(always)]
+    pub fn position(&self) -> Option<&Position> {
+        self.position.as_ref()
+    }
+
+    #[cfg(feature = "std")]
     #[inline]
     pub(crate) fn to_parts(&self) -> (Vec<usize>, Vec<u8>) {
         (self.seps.to_vec(), self.slice.to_vec())
@@ -94,9 +134,10 @@ impl<'a> ZeroCopyByteRecord<'a> {
     /// out-of-bounds.
     ///
     /// The field's bytes will be given as-is, quoted or unquoted, and won't be
-    /// unescaped at all.
+    /// unescaped at all (they might still be trimmed if the reader was
+    /// configured to do so).
     #[inline]
-    pub fn get(&self, index: usize) -> Option<&[u8]> {
+    pub fn get(&self, index: usize) -> Option<&'a [u8]> {
         let len = self.seps.len();
 
         if index > len {
@@ -106,7 +147,7 @@ impl<'a> ZeroCopyByteRecord<'a> {
         let start = if index == 0 {
             0
         } else {
-            self.seps[index - 1] + 1
+            self.seps[index - 1] + self.delimiter.len()
         };
 
         let end = if index == len {
@@ -115,7 +156,9 @@ impl<'a> ZeroCopyByteRecord<'a> {
             self.seps[index]
         };
 
-        Some(&self.slice[start..end])
+        let cell = &self.slice[start..end];
+
+        Some(if self.trim { cell.trim_ascii() } else { cell })
     }
 
     /// Returns the nth field of the zero copy byte record, if it is not
@@ -128,9 +171,9 @@ impl<'a> ZeroCopyByteRecord<'a> {
     /// The overhead vs. [`Self::get`] is only constant (we trim a leading and
     /// trailing quote if required).
     #[inline]
-    pub fn unquote(&self, index: usize) -> Option<&[u8]> {
+    pub fn unquote(&self, index: usize) -> Option<&'a [u8]> {
         self.get(index)
-            .map(|cell| unquoted(cell, self.quote).unwrap_or(cell))
+            .map(|cell| self.quoting.unquoted(cell).unwrap_or(cell))
     }
 
     /// Returns the nth field of the zero copy byte record, if it is not
@@ -143,22 +186,47 @@ impl<'a> ZeroCopyByteRecord<'a> {
     /// A [`Cow::Owned`] will be returned if the field actually needed
     /// unescaping, else a [`Cow::Borrowed`] will be returned.
     #[inline]
-    pub fn unescape(&self, index: usize) -> Option<Cow<'_, [u8]>> {
+    pub fn unescape(&self, index: usize) -> Option<Cow<'a, [u8]>> {
         self.get(index).map(|cell| {
-            if let Some(trimmed) = unquoted(cell, self.quote) {
-                unescape(trimmed, self.quote)
+            if let Some((quoted, rest)) = self.quoting.split_quoted(cell) {
+                let Quoting {
+                    quote,
+                    escape,
+                    double_quote,
+                    ..
+                } = self.quoting;
+
+                let unescaped = unescape_with(quoted, quote, escape, double_quote);
+
+                if rest.is_empty() {
+                    unescaped
+                } else {
+                    let mut output = unescaped.into_owned();
+                    output.extend_from_slice(rest);
+
+                    Cow::Owned(output)
+                }
             } else {
                 Cow::Borrowed(cell)
             }
         })
     }
 
-    fn read_byte_record(&self, record: &mut ByteRecord) {
+    pub(crate) fn read_byte_record(&self, record: &mut ByteRecord) {
         record.clear();
+        record.position = self.position;
 
         for cell in self.iter() {
-            if let Some(trimmed) = unquoted(cell, self.quote) {
-                unescape_to(trimmed, self.quote, &mut record.data);
+            if let Some((quoted, rest)) = self.quoting.split_quoted(cell) {
+                let Quoting {
+                    quote,
+                    escape,
+                    double_quote,
+                    ..
+                } = self.quoting;
+
+                unescape_with_to(quoted, quote, escape, double_quote, &mut record.data);
+                record.data.extend_from_slice(rest);
 
                 let bounds_len = record.bounds.len();
 
@@ -183,6 +251,7 @@ impl<'a> ZeroCopyByteRecord<'a> {
         record
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     pub(crate) fn to_byte_record_in_reverse(&self) -> ByteRecord {
         let mut record = ByteRecord::new();
@@ -193,6 +262,39 @@ impl<'a> ZeroCopyByteRecord<'a> {
 
         record
     }
+
+    /// Deserialize the record into any type implementing [`serde::Deserialize`].
+    ///
+    /// If `headers` are given, struct fields will be mapped to the columns
+    /// having the same name, else they will be mapped by position.
+    ///
+    /// Fields are unescaped if needed, which means `&str` or `&[u8]` can be
+    /// borrowed from the underlying buffer, as long as the corresponding
+    /// fields do not contain escaped quotes. Use a [`Cow`] with
+    /// `#[serde(borrow)]` to handle both cases.
+    ///
+    /// Note that `headers` are indexed each time this method is called. Use
+    /// [`Self::deserialize_with_index`] to avoid this cost when deserializing
+    /// many records.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<D: serde::Deserialize<'a>>(
+        &self,
+        headers: Option<&ByteRecord>,
+    ) -> error::Result<D> {
+        let index = headers.map(|headers| ByteHeadersIndex::new(headers.clone(), true));
+
+        deserialize_record(self, index.as_ref())
+    }
+
+    /// Deserialize the record into any type implementing [`serde::Deserialize`],
+    /// mapping struct fields to columns using the given [`ByteHeadersIndex`].
+    #[cfg(feature = "serde")]
+    pub fn deserialize_with_index<D: serde::Deserialize<'a>>(
+        &self,
+        headers: &ByteHeadersIndex,
+    ) -> error::Result<D> {
+        deserialize_record(self, Some(headers))
+    }
 }
 
 impl fmt::Debug for ZeroCopyByteRecord<'_> {
@@ -292,6 +394,7 @@ impl Index<usize> for ZeroCopyByteRecord<'_> {
 pub struct ByteRecord {
     pub(crate) data: Vec<u8>,
     pub(crate) bounds: Vec<(usize, usize)>,
+    position: Option<Position>,
 }
 
 impl ByteRecord {
@@ -317,6 +420,22 @@ impl ByteRecord {
     pub fn clear(&mut self) {
         self.data.clear();
         self.bounds.clear();
+        self.position = None;
+    }
+
+    /// Returns the position of the record in the stream it was read from, if
+    /// known.
+    ///
+    /// Note that two records with different positions can still be equal.
+    #[inline]
+    pub fn position(&self) -> Option<&Position> {
+        self.position.as_ref()
+    }
+
+    /// Set the position of the record.
+    #[inline]
+    pub fn set_position(&mut self, position: Option<Position>) {
+        self.position = position;
     }
 
     /// Shortens the record, keeping the first `len` elements and dropping the
@@ -379,7 +498,9 @@ impl ByteRecord {
     /// target into this record's bytes directly.
     #[inline]
     pub fn fmt_field<F: fmt::Display>(&mut self, target: &F) {
-        write!(&mut self.data, "{}", target).unwrap();
+        use fmt::Write;
+
+        write!(AppendOnlyView::new(&mut self.data), "{}", target).unwrap();
 
         let bounds_len = self.bounds.len();
 
@@ -412,6 +533,7 @@ impl ByteRecord {
         self.bounds.push((start, self.data.len()));
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     fn push_field_in_reverse(&mut self, bytes: &[u8]) {
         self.data.extend_from_slice(bytes);
@@ -451,6 +573,36 @@ impl ByteRecord {
         }
     }
 
+    /// Deserialize the record into any type implementing [`serde::Deserialize`].
+    ///
+    /// If `headers` are given, struct fields will be mapped to the columns
+    /// having the same name, else they will be mapped by position. Empty
+    /// fields are deserialized as `None` for `Option` fields.
+    ///
+    /// Note that `headers` are indexed each time this method is called. Use
+    /// [`Self::deserialize_with_index`] or [`Reader::deserialize`](crate::Reader::deserialize)
+    /// to avoid this cost when deserializing many records.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'de, D: serde::Deserialize<'de>>(
+        &'de self,
+        headers: Option<&ByteRecord>,
+    ) -> error::Result<D> {
+        let index = headers.map(|headers| ByteHeadersIndex::new(headers.clone(), true));
+
+        deserialize_record(&self, index.as_ref())
+    }
+
+    /// Deserialize the record into any type implementing [`serde::Deserialize`],
+    /// mapping struct fields to columns using the given [`ByteHeadersIndex`].
+    #[cfg(feature = "serde")]
+    pub fn deserialize_with_index<'de, D: serde::Deserialize<'de>>(
+        &'de self,
+        headers: &ByteHeadersIndex,
+    ) -> error::Result<D> {
+        deserialize_record(&self, Some(headers))
+    }
+
+    #[cfg(feature = "std")]
     pub(crate) fn reverse(&mut self) {
         self.data.reverse();
         self.bounds.reverse();
@@ -665,6 +817,25 @@ impl<'r> ByteRecordBuilder<'r> {
         self.start += 1;
     }
 
+    /// Replaces the whole record by the unescaped fields of the given zero
+    /// copy record.
+    #[inline]
+    pub(crate) fn set_from_zero_copy(&mut self, record: &ZeroCopyByteRecord) {
+        record.read_byte_record(self.record);
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn fields(&self) -> usize {
+        self.record.bounds.len()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn set_position(&mut self, position: Position) {
+        self.record.set_position(Some(position));
+    }
+
     #[inline(always)]
     pub(crate) fn bump(&mut self) {
         self.start +=
@@ -725,6 +896,13 @@ mod string_record {
             self.inner.clear();
         }
 
+        /// Returns the position of the record in the stream it was read from,
+        /// if known.
+        #[inline(always)]
+        pub fn position(&self) -> Option<&Position> {
+            self.inner.position()
+        }
+
         #[inline(always)]
         pub(crate) fn as_inner_mut(&mut self) -> &mut ByteRecord {
             &mut self.inner
@@ -750,8 +928,8 @@ mod string_record {
         #[inline]
         pub fn get(&self, index: usize) -> Option<&str> {
             self.inner.get(index).map(|slice| {
-                debug_assert!(std::str::from_utf8(slice).is_ok());
-                unsafe { std::str::from_utf8_unchecked(slice) }
+                debug_assert!(core::str::from_utf8(slice).is_ok());
+                unsafe { core::str::from_utf8_unchecked(slice) }
             })
         }
 
@@ -859,7 +1037,7 @@ mod string_record {
 
                 self.current_forward += 1;
 
-                Some(unsafe { std::str::from_utf8_unchecked(&self.record.data[start..end]) })
+                Some(unsafe { core::str::from_utf8_unchecked(&self.record.data[start..end]) })
             }
         }
 
@@ -889,7 +1067,7 @@ mod string_record {
 
                 let (start, end) = self.record.bounds[self.current_backward];
 
-                Some(unsafe { std::str::from_utf8_unchecked(&self.record.data[start..end]) })
+                Some(unsafe { core::str::from_utf8_unchecked(&self.record.data[start..end]) })
             }
         }
     }
@@ -902,17 +1080,26 @@ pub use string_record::*;
 mod tests {
     use super::*;
 
+    use alloc::vec;
+
     #[test]
     fn test_zero_copy_byte_record() {
-        let record = ZeroCopyByteRecord::new(b"name,surname,age", &[4, 12], b'"');
+        let record = ZeroCopyByteRecord::new(
+            b"name,surname,age",
+            &[4, 12],
+            Delimiter::from(b','),
+            Terminator::CRLF,
+            Quoting::default(),
+            false,
+        );
 
         assert_eq!(record.len(), 3);
 
         let expected: Vec<&[u8]> = vec![b"name", b"surname", b"age"];
         assert_eq!(record.iter().collect::<Vec<_>>(), expected);
 
-        for i in 0..expected.len() {
-            assert_eq!(record.get(i), Some(expected[i]));
+        for (i, cell) in expected.iter().enumerate() {
+            assert_eq!(record.get(i), Some(*cell));
         }
 
         assert_eq!(record.get(4), None);
@@ -923,7 +1110,7 @@ mod tests {
         let mut record = ByteRecord::new();
 
         assert_eq!(record.len(), 0);
-        assert_eq!(record.is_empty(), true);
+        assert!(record.is_empty());
         assert_eq!(record.get(0), None);
 
         record.push_field(b"name");
@@ -950,6 +1137,7 @@ mod tests {
         assert_eq!(record, brec!["45", "5.6", "test"]);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_write_field() {
         let mut record = ByteRecord::new();
@@ -975,6 +1163,7 @@ mod tests {
         assert_eq!(record.iter().collect::<Vec<_>>(), vec![b"test", b"next"]);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_reverse_byte_record() {
         let record = brec!["name", "surname", "age"];
@@ -1,8 +1,8 @@
-use std::iter::FusedIterator;
+use core::iter::FusedIterator;
 
 #[cfg(target_arch = "x86_64")]
 mod x86_64 {
-    use std::marker::PhantomData;
+    use core::marker::PhantomData;
 
     use crate::ext::Pointer;
 
@@ -182,7 +182,7 @@ mod aarch64 {
         uint8x16_t, vceqq_u8, vdupq_n_u8, vget_lane_u64, vld1q_u8, vorrq_u8, vreinterpret_u64_u8,
         vreinterpretq_u16_u8, vshrn_n_u16,
     };
-    use std::marker::PhantomData;
+    use core::marker::PhantomData;
 
     use crate::ext::Pointer;
 
@@ -492,6 +492,8 @@ impl Indices<'_, '_> {
 mod tests {
     use super::*;
 
+    use alloc::vec::Vec;
+
     use memchr::arch::all::memchr::Three;
 
     static TEST_STRING: &[u8] = b"name,\"surname\",age,color,oper\n,\n,\nation,punctuation\nname,surname,age,color,operation,punctuation";
@@ -1,7 +1,10 @@
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::ops::Range;
+use std::sync::Arc;
 
+use crate::dialect::{Delimiter, Dialect, Terminator};
 use crate::error::{self, Error, ErrorKind};
+use crate::preamble::SkipLines;
 use crate::reader::Reader;
 use crate::records::ByteRecord;
 use crate::splitter::Splitter;
@@ -11,6 +14,7 @@ use crate::zero_copy_reader::{ZeroCopyReader, ZeroCopyReaderBuilder};
 #[derive(Debug)]
 struct SeekerSample {
     headers: ByteRecord,
+    preamble: Vec<u8>,
     record_count: u64,
     max_record_size: u64,
     median_record_size: u64,
@@ -35,11 +39,12 @@ impl SeekerSample {
         let mut csv_reader = csv_reader_builder.from_reader(&mut reader);
 
         let headers = csv_reader.byte_headers()?.clone();
+        let preamble = csv_reader.preamble()?.to_vec();
 
         let first_record_position = if csv_reader.has_headers() {
             initial_position + csv_reader.position()
         } else {
-            initial_position
+            initial_position + preamble.len() as u64
         };
 
         let mut i: u64 = 0;
@@ -78,6 +83,7 @@ impl SeekerSample {
 
         Ok(Some(Self {
             headers,
+            preamble,
             record_count: i,
             max_record_size: *record_sizes.last().unwrap(),
             median_record_size: record_sizes[record_sizes.len() / 2],
@@ -106,8 +112,7 @@ fn cosine(profile: &[f64], other: impl Iterator<Item = usize>) -> f64 {
 
 /// Builds a [`Seeker`] with given configuration.
 pub struct SeekerBuilder {
-    delimiter: u8,
-    quote: u8,
+    dialect: Dialect,
     has_headers: bool,
     buffer_capacity: usize,
     sample_size: u64,
@@ -117,8 +122,7 @@ pub struct SeekerBuilder {
 impl Default for SeekerBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
             buffer_capacity: 8192,
             has_headers: true,
             sample_size: 128,
@@ -142,11 +146,27 @@ impl SeekerBuilder {
 
     /// Set the delimiter to be used by the created [`Seeker`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`Seeker`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -156,7 +176,60 @@ impl SeekerBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`Seeker`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`Seeker`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`Seeker`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`Seeker`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`Seeker`].
+    ///
+    /// Lines starting with this byte will be skipped entirely when sampling
+    /// the stream and when looking ahead to find record boundaries.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
         self
     }
 
@@ -186,6 +259,34 @@ impl SeekerBuilder {
         self
     }
 
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`Seeker`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`Seeker::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`Seeker`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
+        self
+    }
+
     /// Indicate whether first record must be understood as a header.
     ///
     /// Will default to `true`.
@@ -201,14 +302,18 @@ impl SeekerBuilder {
 
         builder
             .buffer_capacity(self.buffer_capacity)
-            .delimiter(self.delimiter)
-            .quote(self.quote)
             .has_headers(self.has_headers);
 
+        builder.dialect = self.dialect.clone();
+
         match SeekerSample::from_reader(&mut reader, &builder, self.sample_size) {
             Ok(Some(sample)) => {
                 builder.has_headers(false).flexible(true);
 
+                // NOTE: the preamble must only be skipped when reading from
+                // the initial position.
+                let skip_lines = std::mem::take(&mut builder.dialect.skip_lines);
+
                 Ok(Some(Seeker {
                     inner: reader,
                     lookahead_factor: self.lookahead_factor,
@@ -218,6 +323,7 @@ impl SeekerBuilder {
                     sample,
                     builder,
                     has_headers: self.has_headers,
+                    skip_lines,
                 }))
             }
             Ok(None) => Ok(None),
@@ -268,8 +374,9 @@ pub struct Seeker<R> {
     sample: SeekerSample,
     lookahead_factor: u64,
     scratch: Vec<u8>,
-    builder: ZeroCopyReaderBuilder,
+    pub(crate) builder: ZeroCopyReaderBuilder,
     has_headers: bool,
+    skip_lines: SkipLines,
 }
 
 impl<R: Read + Seek> Seeker<R> {
@@ -286,6 +393,12 @@ impl<R: Read + Seek> Seeker<R> {
         self.sample.initial_position
     }
 
+    /// Returns the raw preamble lines that were skipped before the header row,
+    /// terminators included.
+    pub fn preamble(&self) -> &[u8] {
+        &self.sample.preamble
+    }
+
     /// Returns the absolute byte offset of the first record (excluding header)
     /// of the seekable stream.
     #[inline(always)]
@@ -395,12 +508,18 @@ impl<R: Read + Seek> Seeker<R> {
             .read_to_end(&mut self.scratch)?;
 
         let mut unquoted_reader = self.builder.from_reader(self.scratch.as_slice());
+        let expected_field_count = self.sample.headers.len();
+
+        // NOTE: we cannot fall within a quoted cell if there is no quoting
+        if !self.builder.dialect.quoting {
+            return Ok(lookahead(&mut unquoted_reader, expected_field_count)?
+                .map(|(pos, record)| (from_pos + pos, record)));
+        }
+
         let mut quoted_reader = self
             .builder
             .from_reader(Cursor::new(b"\"").chain(self.scratch.as_slice()));
 
-        let expected_field_count = self.sample.headers.len();
-
         let unquoted = lookahead(&mut unquoted_reader, expected_field_count)?;
         let quoted = lookahead(&mut quoted_reader, expected_field_count)?;
 
@@ -505,11 +624,27 @@ impl<R: Read + Seek> Seeker<R> {
             self.sample.first_record_position,
         );
 
-        let mut reverse_csv_reader = self.builder.from_reader(reverse_reader);
+        // NOTE: when reading in reverse, the comment char of a comment line
+        // is found at the end of the record, so we need to filter them ourselves.
+        // Escape chars cannot be supported either since they would follow the
+        // escaped byte. Finally, multi-byte delimiters must be searched
+        // backwards.
+        let comment = self.builder.dialect.comment;
+        let mut reverse_builder = self.builder.clone();
+        reverse_builder.comment(None).escape(None);
+        reverse_builder.dialect.delimiter = reverse_builder.dialect.delimiter.reversed();
+
+        let mut reverse_csv_reader = reverse_builder.from_reader(reverse_reader);
+
+        while let Some(record) = reverse_csv_reader.read_byte_record()? {
+            if comment.is_some() && record.as_slice().last().copied() == comment {
+                continue;
+            }
+
+            return Ok(Some(record.to_byte_record_in_reverse()));
+        }
 
-        reverse_csv_reader
-            .read_byte_record()
-            .map(|record_opt| record_opt.map(|record| record.to_byte_record_in_reverse()))
+        Ok(None)
     }
 
     /// Returns the underlying reader without unwinding its position.
@@ -536,6 +671,7 @@ impl<R: Read + Seek> Seeker<R> {
 
         self.inner.seek(pos)?;
         self.builder.has_headers(self.has_headers);
+        self.builder.dialect.skip_lines = self.skip_lines;
 
         Ok(self.builder.to_splitter_builder().from_reader(self.inner))
     }
@@ -562,6 +698,7 @@ impl<R: Read + Seek> Seeker<R> {
 
         self.inner.seek(pos)?;
         self.builder.has_headers(self.has_headers);
+        self.builder.dialect.skip_lines = self.skip_lines;
 
         Ok(self.builder.from_reader(self.inner))
     }
@@ -585,6 +722,7 @@ impl<R: Read + Seek> Seeker<R> {
 
         self.inner.seek(pos)?;
         self.builder.has_headers(self.has_headers);
+        self.builder.dialect.skip_lines = self.skip_lines;
 
         Ok(self.builder.to_reader_builder().from_reader(self.inner))
     }
@@ -608,6 +746,50 @@ mod tests {
         assert_eq!(seeker.last_byte_record().unwrap(), Some(brec!["john"]));
     }
 
+    #[test]
+    fn test_comment() {
+        let data =
+            "# metadata\nname,surname\njohn,landis\n# lucy,rose\nhelly,radio\n# trailing,comment\n";
+
+        let mut seeker = SeekerBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(Cursor::new(data))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(seeker.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(seeker.exact_count(), Some(2));
+        assert_eq!(
+            seeker.first_byte_record().unwrap(),
+            Some(brec!["john", "landis"])
+        );
+        assert_eq!(
+            seeker.last_byte_record().unwrap(),
+            Some(brec!["helly", "radio"])
+        );
+    }
+
+    #[test]
+    fn test_multi_byte_delimiter() {
+        let data = "name¦surname\njohn¦landis\nlucy¦\"ro¦se\"\n";
+
+        let mut seeker = SeekerBuilder::new()
+            .delimiter_bytes("¦".as_bytes())
+            .from_reader(Cursor::new(data))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(seeker.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(
+            seeker.first_byte_record().unwrap(),
+            Some(brec!["john", "landis"])
+        );
+        assert_eq!(
+            seeker.last_byte_record().unwrap(),
+            Some(brec!["lucy", "ro¦se"])
+        );
+    }
+
     #[test]
     fn test_middle_of_crlf() {
         let data =
@@ -630,4 +812,36 @@ mod tests {
         let (offset, _) = seeker.find_record_after(27).unwrap().unwrap();
         assert_eq!(offset, 38);
     }
+
+    #[test]
+    fn test_skip_lines() -> error::Result<()> {
+        let data = "sep=,\n\"metadata\nname,surname\njohn,landis\nlucy,rose\nhelly,radio\n";
+
+        let mut seeker = SeekerBuilder::new()
+            .skip_lines(2)
+            .from_reader(Cursor::new(data))?
+            .unwrap();
+
+        assert_eq!(seeker.preamble(), b"sep=,\n\"metadata\n");
+        assert_eq!(seeker.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(seeker.first_record_position(), 29);
+        assert_eq!(seeker.exact_count(), Some(3));
+        assert_eq!(seeker.first_byte_record()?, Some(brec!["john", "landis"]));
+        assert_eq!(seeker.last_byte_record()?, Some(brec!["helly", "radio"]));
+
+        let mut reader = seeker.into_reader()?;
+
+        assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+        assert_eq!(reader.byte_records().count(), 3);
+
+        let seeker = SeekerBuilder::new()
+            .has_headers(false)
+            .skip_lines(2)
+            .from_reader(Cursor::new(data))?
+            .unwrap();
+
+        assert_eq!(seeker.first_record_position(), 16);
+
+        Ok(())
+    }
 }
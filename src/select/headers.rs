@@ -154,9 +154,9 @@ mod tests {
         let index = ByteHeadersIndex::new(headers.clone(), true);
 
         assert_eq!(&headers, index.as_ref());
-        assert_eq!(index.has_names(), true);
+        assert!(index.has_names());
         assert_eq!(index.len(), 4);
-        assert_eq!(index.is_empty(), false);
+        assert!(!index.is_empty());
         assert_eq!(index.first_column_index_by_name("surname"), Some(1));
     }
 }
@@ -1,23 +1,30 @@
-use std::io::Read;
+use std::io::{Read, Seek};
+use std::sync::Arc;
 
 use crate::buffer::ScratchBuffer;
-use crate::core::{CoreReader, ReadResult};
+use crate::checkpoint::{parse_raw_record, Checkpoint};
+use crate::core::CoreReader;
+use crate::dialect::{Delimiter, Dialect, Terminator};
 use crate::error;
-use crate::utils::{trim_bom, trim_trailing_crlf};
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::preamble::SkipLines;
+use crate::tracker::{Step, Tracker};
+use crate::utils::{count_lines, trim_bom};
 
 /// Builds a [`Splitter`] with given configuration.
 pub struct SplitterBuilder {
-    delimiter: u8,
-    quote: u8,
-    buffer_capacity: Option<usize>,
-    has_headers: bool,
+    pub(crate) dialect: Dialect,
+    pub(crate) limits: Limits,
+    pub(crate) buffer_capacity: Option<usize>,
+    pub(crate) has_headers: bool,
 }
 
 impl Default for SplitterBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
+            limits: Limits::default(),
             buffer_capacity: None,
             has_headers: true,
         }
@@ -39,11 +46,27 @@ impl SplitterBuilder {
 
     /// Set the delimiter to be used by the created [`Splitter`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`Splitter`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -53,7 +76,89 @@ impl SplitterBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`Splitter`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`Splitter`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`Splitter`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`Splitter`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`Splitter`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`Splitter`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`Splitter::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`Splitter`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
         self
     }
 
@@ -65,6 +170,58 @@ impl SplitterBuilder {
         self
     }
 
+    /// Indicate whether the created [`Splitter`] should be strict regarding
+    /// quoting, i.e. whether it should return an error when finding a quote
+    /// in the middle of an unquoted field, or unexpected data after a closing
+    /// quote, instead of trying its best to make sense of it.
+    ///
+    /// A strict [`Splitter`] will also return an error when reaching the end of
+    /// the stream in the middle of a quoted field, which typically happens
+    /// when reading truncated data. A non-strict one will yield the truncated
+    /// record as is, but [`Splitter::ended_in_quote`] can be checked afterwards.
+    ///
+    /// Will default to `false`.
+    pub fn strict(&mut self, yes: bool) -> &mut Self {
+        self.dialect.strict = yes;
+        self
+    }
+
+    /// Set the maximum number of bytes a single record may span in the stream
+    /// read by the created [`Splitter`]. Reading a larger record will return an
+    /// [`ErrorKind::RecordTooLarge`](crate::ErrorKind::RecordTooLarge) error
+    /// instead of buffering it indefinitely.
+    ///
+    /// Will default to no limit.
+    pub fn max_record_size(&mut self, size: usize) -> &mut Self {
+        self.limits.max_record_size = Some(size);
+        self
+    }
+
+    /// Indicate whether the created [`Splitter`] should skip empty lines.
+    ///
+    /// When set to `false`, empty lines will be yielded as empty records, and
+    /// counted as such by [`Splitter::count_records`].
+    ///
+    /// Will default to `true`.
+    pub fn skip_empty_lines(&mut self, yes: bool) -> &mut Self {
+        self.dialect.skip_empty_lines = yes;
+        self
+    }
+
+    /// Indicate whether the created [`Splitter`] should only consider quotes
+    /// found at the very beginning of a field as opening a quoted field, like
+    /// the `csv` crate does, instead of entering the quoted state on any
+    /// quote. This way, dirty data such as `joh"n` won't misalign records.
+    ///
+    /// This mode is slightly slower and makes [`Self::strict`] only report
+    /// unexpected data after closing quotes.
+    ///
+    /// Will default to `false`.
+    pub fn lenient_quotes(&mut self, yes: bool) -> &mut Self {
+        self.dialect.lenient_quotes = yes;
+        self
+    }
+
     /// Set the capacity of the created [`Splitter`]'s buffered reader.
     pub fn buffer_capacity(&mut self, capacity: usize) -> &mut Self {
         self.buffer_capacity = Some(capacity);
@@ -76,13 +233,44 @@ impl SplitterBuilder {
     pub fn from_reader<R: Read>(&self, reader: R) -> Splitter<R> {
         Splitter {
             buffer: ScratchBuffer::with_optional_capacity(self.buffer_capacity, reader),
-            inner: CoreReader::new(self.delimiter, self.quote),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
             headers: Vec::new(),
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
             has_read: false,
             has_headers: self.has_headers,
             must_reemit_headers: !self.has_headers,
+            headers_byte: 0,
+            headers_line: 1,
         }
     }
+
+    /// Create a new [`Splitter`] resuming reading where the one a [`Checkpoint`]
+    /// was taken from stopped, using the provided reader implementing both
+    /// [`std::io::Read`] and [`std::io::Seek`].
+    ///
+    /// The given reader must stand where the checkpointed one started, e.g.
+    /// at the beginning of the same file. The dialect recorded by the
+    /// checkpoint will be used instead of the one of this builder.
+    pub fn resume_from_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+        checkpoint: &Checkpoint,
+    ) -> error::Result<Splitter<R>> {
+        let mut splitter = self.from_reader(reader);
+
+        splitter.inner = CoreReader::new(checkpoint.dialect());
+        splitter.headers = checkpoint.raw_headers().1;
+        splitter.has_headers = checkpoint.has_headers();
+        splitter.must_reemit_headers = false;
+        splitter.has_read = true;
+
+        splitter.seek(checkpoint.position())?;
+
+        Ok(splitter)
+    }
 }
 
 /// An already configured CSV record splitter.
@@ -96,10 +284,17 @@ impl SplitterBuilder {
 pub struct Splitter<R> {
     buffer: ScratchBuffer<R>,
     inner: CoreReader,
+    tracker: Tracker,
     headers: Vec<u8>,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
     has_read: bool,
     has_headers: bool,
     must_reemit_headers: bool,
+    // NOTE: byte offset of the first record, used when it must be reemitted
+    headers_byte: u64,
+    headers_line: u64,
 }
 
 impl<R: Read> Splitter<R> {
@@ -115,6 +310,18 @@ impl<R: Read> Splitter<R> {
         self.has_headers
     }
 
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
+
     /// Attempt to return a reference to this splitter's first record.
     pub fn byte_headers(&mut self) -> error::Result<&[u8]> {
         self.on_first_read()?;
@@ -122,6 +329,23 @@ impl<R: Read> Splitter<R> {
         Ok(&self.headers)
     }
 
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    pub fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read()?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read()?;
+
+        Ok(self.had_bom)
+    }
+
     #[inline(always)]
     fn on_first_read(&mut self) -> error::Result<()> {
         if self.has_read {
@@ -131,6 +355,12 @@ impl<R: Read> Splitter<R> {
         let input = self.buffer.fill_buf()?;
         let bom_len = trim_bom(input);
         self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        self.preamble = self.skip_lines.skip(self.buffer.inner_mut(), &self.inner)?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
+        self.headers_byte = self.buffer.position();
+        self.headers_line = self.tracker.line;
 
         if let Some(record) = self.split_record_impl()? {
             self.headers = record.to_vec();
@@ -145,9 +375,10 @@ impl<R: Read> Splitter<R> {
 
     /// Consume the reader completely to count the number of records as fast as
     /// possible.
+    ///
+    /// Lines are not tracked while counting, so the line of a [`Checkpoint`]
+    /// taken afterwards is meaningless.
     pub fn count_records(&mut self) -> error::Result<u64> {
-        use ReadResult::*;
-
         self.on_first_read()?;
         self.buffer.reset();
 
@@ -158,50 +389,39 @@ impl<R: Read> Splitter<R> {
             self.must_reemit_headers = false;
         }
 
+        self.tracker.start(self.buffer.position());
+
         loop {
             let input = self.buffer.fill_buf()?;
+            let read = self.inner.split_record(input);
 
-            let (result, pos) = self.inner.split_record(input);
-
-            self.buffer.consume(pos);
-
-            match result {
-                End => break,
-                InputEmpty | Cr | Lf => continue,
-                Record => {
-                    count += 1;
-                }
-            };
+            match self.tracker.count_step(self.buffer.inner_mut(), read)? {
+                Step::End => break,
+                Step::Continue => continue,
+                Step::Record => count += 1,
+            }
         }
 
         Ok(count)
     }
 
     fn split_record_impl(&mut self) -> error::Result<Option<&[u8]>> {
-        use ReadResult::*;
-
         self.buffer.reset();
 
+        self.tracker.start(self.buffer.position());
+
         loop {
             let input = self.buffer.fill_buf()?;
+            let read = self.inner.split_record(input);
 
-            let (result, pos) = self.inner.split_record(input);
-
-            match result {
-                End => {
-                    self.buffer.consume(pos);
-                    return Ok(None);
-                }
-                Cr | Lf => {
-                    self.buffer.consume(pos);
-                }
-                InputEmpty => {
-                    self.buffer.save();
-                }
-                Record => {
-                    return Ok(Some(trim_trailing_crlf(self.buffer.flush(pos))));
-                }
-            };
+            match self
+                .tracker
+                .split_step(&mut self.buffer, read, &mut self.inner)?
+            {
+                Step::End => return Ok(None),
+                Step::Continue => continue,
+                Step::Record => return Ok(Some(self.inner.trim_terminator(self.buffer.flushed()))),
+            }
         }
     }
 
@@ -241,22 +461,71 @@ impl<R: Read> Splitter<R> {
         }
     }
 
+    /// Attempt to take a [`Checkpoint`] recording the position of the next
+    /// record to read, so that reading can later be resumed using
+    /// [`SplitterBuilder::resume_from_reader`].
+    pub fn checkpoint(&mut self) -> error::Result<Checkpoint> {
+        self.on_first_read()?;
+
+        let position = if self.must_reemit_headers {
+            Position {
+                byte: self.headers_byte,
+                line: self.headers_line,
+                record: 0,
+            }
+        } else {
+            self.tracker.position_at(self.buffer.position())
+        };
+
+        Ok(Checkpoint::new(
+            position,
+            parse_raw_record(&self.inner.dialect(), &self.headers),
+            self.has_headers,
+            self.inner.dialect(),
+        ))
+    }
+
     /// Returns the current byte offset of the reader in the wrapped stream.
     #[inline(always)]
     pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
         if self.must_reemit_headers {
-            0
+            self.tracker.record_position().byte
         } else {
             self.buffer.position()
         }
     }
 }
 
+impl<R: Read + Seek> Splitter<R> {
+    /// Seek the underlying stream to the given [`Position`], typically taken
+    /// from a previously read record, so that the next record read will be
+    /// the one starting there.
+    ///
+    /// Buffered data is discarded, but already read headers are kept and
+    /// records will continue to be numbered from `pos.record`.
+    ///
+    /// **BEWARE**: `pos.byte` is relative to the beginning of the stream as
+    /// reported by [`Self::position`], and must be the exact beginning of a
+    /// CSV record, or yielded records will therefore be incorrect.
+    pub fn seek(&mut self, pos: Position) -> error::Result<()> {
+        self.on_first_read()?;
+
+        self.buffer.seek(pos.byte)?;
+        self.inner.reset();
+        self.must_reemit_headers = false;
+        self.tracker.seek(pos);
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
     use super::*;
+    use crate::error::ErrorKind;
 
     fn count_records(data: &str, capacity: usize) -> u64 {
         let mut splitter = SplitterBuilder::with_capacity(capacity)
@@ -271,7 +540,7 @@ mod tests {
             .from_reader(Cursor::new(data));
         let mut count: u64 = 0;
 
-        while let Some(_) = splitter.split_record().unwrap() {
+        while splitter.split_record().unwrap().is_some() {
             count += 1;
         }
 
@@ -284,7 +553,7 @@ mod tests {
         assert_eq!(count_records("", 1024), 0);
 
         // Single cells with various empty lines
-        let tests = vec![
+        let tests = [
             "name\njohn\nlucy",
             "name\njohn\nlucy\n",
             "name\n\njohn\r\nlucy\n",
@@ -328,6 +597,378 @@ mod tests {
         assert_eq!(split_records(data, 1024), 3);
     }
 
+    #[test]
+    fn test_strict() -> error::Result<()> {
+        let data = "name,surname\n\"john\",\"landis\"\nlucy,ro\"se\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .strict(true)
+                .from_reader(Cursor::new(data));
+
+            let err = splitter.count_records().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::MalformedQuote { pos: Some((36, 2)) }
+            ));
+        }
+
+        // Splitting must resume with the record following the malformed one
+        let data = "a,b\n\"c\nd\"e,f\ng,h\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .strict(true)
+                .has_headers(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.split_record()?, Some(b"a,b".as_slice()));
+
+            let err = splitter.split_record().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::MalformedQuote { pos: Some((9, 1)) }
+            ));
+
+            assert_eq!(splitter.split_record()?, Some(b"g,h".as_slice()));
+            assert_eq!(splitter.split_record()?, None);
+        }
+
+        // The whole delimiter, or terminator, must follow a closing quote
+        let tests = [
+            (b"||".as_slice(), "\"a\"|x||b\nc||d\n", b"c||d".as_slice()),
+            (b",".as_slice(), "\"a\"\rb,c\nd,e\n", b"d,e".as_slice()),
+        ];
+
+        for (delimiter, data, expected_record) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut splitter = SplitterBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .has_headers(false)
+                    .delimiter_bytes(delimiter)
+                    .from_reader(Cursor::new(data));
+
+                let err = splitter.split_record().unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::MalformedQuote { pos: Some((4, 0)) }
+                ));
+
+                assert_eq!(splitter.split_record()?, Some(expected_record));
+                assert_eq!(splitter.split_record()?, None);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unterminated_quote() -> error::Result<()> {
+        let data = "name\njohn\n\"lucy";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .strict(true)
+                .from_reader(Cursor::new(data));
+
+            let err = splitter.count_records().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::UnterminatedQuote { pos: Some((10, 2)) }
+            ));
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .strict(true)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.split_record()?, Some(b"john".as_slice()));
+            assert!(splitter.split_record().is_err());
+            assert!(splitter.ended_in_quote());
+
+            // Default should remain lenient, but still remember it
+            let mut splitter =
+                SplitterBuilder::with_capacity(capacity).from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.count_records()?, 2);
+            assert!(splitter.ended_in_quote());
+
+            let mut splitter =
+                SplitterBuilder::with_capacity(capacity).from_reader(Cursor::new("name\njohn\n"));
+
+            assert_eq!(splitter.count_records()?, 1);
+            assert!(!splitter.ended_in_quote());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_record_size() -> error::Result<()> {
+        let data = format!("name\n\"john\n{}", "lucy\n".repeat(32));
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .max_record_size(16)
+                .from_reader(Cursor::new(&data));
+
+            let err = splitter.count_records().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::RecordTooLarge {
+                    pos: Some((5, 1)),
+                    limit: 16
+                }
+            ));
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .max_record_size(16)
+                .from_reader(Cursor::new(&data));
+
+            assert!(splitter.split_record().is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_record_size_after_skipped_lines() -> error::Result<()> {
+        let comment = format!("#{}\n", "x".repeat(63));
+        let data = format!(
+            "{comment}\n{comment}name,surname\njohn,landis\n{comment}lucy,\"rose\n{}",
+            "a,b\n".repeat(20)
+        );
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .max_record_size(32)
+                .from_reader(Cursor::new(&data));
+
+            let err = splitter.count_records().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::RecordTooLarge {
+                    pos: Some((221, 2)),
+                    limit: 32
+                }
+            ));
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .max_record_size(32)
+                .from_reader(Cursor::new(&data));
+
+            assert_eq!(splitter.split_record()?, Some(b"john,landis".as_slice()));
+
+            let err = splitter.split_record().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::RecordTooLarge {
+                    pos: Some((221, 2)),
+                    limit: 32
+                }
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_empty_lines() -> error::Result<()> {
+        let data = "name\njohn\n\nlucy\r\n\r\n\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .skip_empty_lines(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.count_records()?, 5);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .skip_empty_lines(false)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = splitter.split_record()? {
+                records.push(record.to_vec());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    b"john".to_vec(),
+                    b"".to_vec(),
+                    b"lucy".to_vec(),
+                    b"".to_vec(),
+                    b"".to_vec()
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_terminator() -> error::Result<()> {
+        let data = "name\x1e\"john\x1e\"\x1e\x1elucy\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .terminator(Terminator::Any(b'\x1e'))
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = splitter.split_record()? {
+                records.push(record.to_vec());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    b"name".to_vec(),
+                    b"\"john\x1e\"".to_vec(),
+                    b"lucy\r\n".to_vec()
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoting() -> error::Result<()> {
+        let data = "name\tquote\njohn\t\"hello\n\"lucy\"\tok\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .delimiter(b'\t')
+                .quoting(false)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = splitter.split_record()? {
+                records.push(record.to_vec());
+            }
+
+            assert_eq!(
+                records,
+                vec![b"john\t\"hello".to_vec(), b"\"lucy\"\tok\"".to_vec()]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_quotes() -> error::Result<()> {
+        let data = "name,surname\njoh\"n,landis\nbéatrice,babka\n\"quoted, ok\",5\"\nfoo,\"bar\"\"\"\n5'11\",x\"y\n\"a\",\n";
+
+        let expected = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(data.as_bytes())
+            .byte_records()
+            .count() as u64;
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.count_records()?, expected);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            let mut count = 0;
+
+            while splitter.split_record()?.is_some() {
+                count += 1;
+            }
+
+            assert_eq!(count, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape() -> error::Result<()> {
+        let data = "name,quote\njohn,\"\\\"\nhello\\\"\"\nlucy,\"\\\\\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .escape(Some(b'\\'))
+                .double_quote(false)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = splitter.split_record()? {
+                records.push(record.to_vec());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    b"john,\"\\\"\nhello\\\"\"".to_vec(),
+                    b"lucy,\"\\\\\"".to_vec()
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comment() -> error::Result<()> {
+        let data = "# metadata\nname\n#john\n\"#lucy\"\n# \"whatever\nbeatrice";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.count_records()?, 3);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = splitter.split_record()? {
+                records.push(record.to_vec());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    b"name".to_vec(),
+                    b"\"#lucy\"".to_vec(),
+                    b"beatrice".to_vec()
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_empty_row() -> error::Result<()> {
         let data = "name\n\"\"\nlucy\n\"\"";
@@ -339,4 +980,74 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_skip_lines() -> error::Result<()> {
+        let data = "sep=,\r\nname,surname\r\njohn,landis\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.count_records()?, 1);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.preamble()?, b"sep=,\r\n");
+            assert_eq!(splitter.byte_headers()?, b"name,surname");
+            assert_eq!(splitter.split_record()?, Some(&b"john,landis"[..]));
+            assert_eq!(splitter.split_record()?, None);
+
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .skip_lines(1)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(splitter.preamble()?, b"sep=,\r\n");
+            assert_eq!(splitter.position(), 7);
+            assert_eq!(splitter.split_record()?, Some(&b"name,surname"[..]));
+            assert_eq!(splitter.position(), 21);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> error::Result<()> {
+        let data =
+            "name,notes\njohn,\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\"\nlast,one\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut splitter = SplitterBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            let mut expected = Vec::new();
+
+            while let Some((pos, record)) = splitter.split_record_with_position()? {
+                expected.push((pos, record.to_vec()));
+            }
+
+            for (index, (pos, record)) in expected.iter().enumerate().rev() {
+                splitter.seek(Position {
+                    byte: *pos,
+                    line: 1,
+                    record: index as u64 + 1,
+                })?;
+
+                assert_eq!(splitter.split_record()?, Some(record.as_slice()));
+                assert_eq!(
+                    splitter.count_records()?,
+                    (expected.len() - index - 1) as u64
+                );
+            }
+
+            assert_eq!(splitter.byte_headers()?, b"name,notes");
+        }
+
+        Ok(())
+    }
 }
@@ -1,19 +1,28 @@
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::{ops::Range, thread};
+
+#[cfg(feature = "std")]
+use memchr::memchr;
+
 use crate::core::{CoreReader, ReadResult};
+use crate::dialect::{Delimiter, Dialect, Terminator};
+use crate::position::Position;
+use crate::preamble::SkipLines;
 use crate::records::{ByteRecord, ByteRecordBuilder};
-use crate::utils::trim_bom;
+use crate::utils::{count_lines, trim_bom};
 
 /// Builds a [`TotalReader`] with given configuration.
 pub struct TotalReaderBuilder {
-    delimiter: u8,
-    quote: u8,
+    dialect: Dialect,
     has_headers: bool,
 }
 
 impl Default for TotalReaderBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
             has_headers: true,
         }
     }
@@ -27,11 +36,27 @@ impl TotalReaderBuilder {
 
     /// Set the delimiter to be used by the created [`TotalReader`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`TotalReader`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -41,7 +66,100 @@ impl TotalReaderBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`TotalReader`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`TotalReader`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`TotalReader`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`TotalReader`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`TotalReader`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Indicate whether the created [`TotalReader`] should only consider
+    /// quotes found at the very beginning of a field as opening a quoted field,
+    /// like the `csv` crate does, instead of entering the quoted state on any
+    /// quote. This way, dirty data such as `joh"n` won't misalign records.
+    ///
+    /// Will default to `false`.
+    pub fn lenient_quotes(&mut self, yes: bool) -> &mut Self {
+        self.dialect.lenient_quotes = yes;
+        self
+    }
+
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`TotalReader`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`TotalReader::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`TotalReader`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
         self
     }
 
@@ -56,14 +174,96 @@ impl TotalReaderBuilder {
     /// Create a [`TotalReader`] from given bytes.
     pub fn from_bytes<'b>(&self, bytes: &'b [u8]) -> TotalReader<'b> {
         TotalReader {
-            inner: CoreReader::new(self.delimiter, self.quote),
+            inner: CoreReader::new(&self.dialect),
+            #[cfg(feature = "std")]
+            dialect: self.dialect.clone(),
             bytes,
             pos: 0,
             headers: ByteRecord::new(),
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: &[],
+            had_bom: false,
             has_read: false,
             has_headers: self.has_headers,
+            line: 1,
+            line_pos: 0,
+            index: 0,
+        }
+    }
+}
+
+/// Outcome of splitting a chunk of the input, given a hypothesis regarding
+/// whether its first byte stands within a quoted field.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct ChunkSplit {
+    // NOTE: the start of the first record is `None` when it began in some
+    // preceding chunk
+    records: Vec<(Option<usize>, usize)>,
+    count: u64,
+    // Start of the record left unfinished at the end of the chunk, if it
+    // began in this chunk
+    pending: Option<usize>,
+    ends_in_quoted_field: bool,
+}
+
+#[cfg(feature = "std")]
+fn split_chunk(
+    dialect: &Dialect,
+    bytes: &[u8],
+    range: Range<usize>,
+    in_quoted_field: bool,
+    is_last: bool,
+    collect: bool,
+) -> ChunkSplit {
+    use ReadResult::*;
+
+    let mut inner = CoreReader::new(dialect);
+
+    if in_quoted_field {
+        inner.start_in_quoted_field();
+    }
+
+    let input = &bytes[..range.end];
+    let mut split = ChunkSplit::default();
+    let mut starting_pos = (!in_quoted_field).then_some(range.start);
+    let mut pos = range.start;
+
+    // NOTE: only the last chunk must be given an empty input, to flush a
+    // possible record without trailing newline
+    while pos < range.end || is_last {
+        let (result, offset) = inner.split_record(&input[pos..]);
+
+        pos += offset;
+
+        match result {
+            End => break,
+            InputEmpty => continue,
+            Cr | Lf | Comment => {
+                starting_pos = Some(pos);
+            }
+            MalformedQuote | UnterminatedQuote => {
+                unreachable!("total reader cannot be strict")
+            }
+            Record => {
+                split.count += 1;
+
+                if collect {
+                    split.records.push((starting_pos, pos));
+                }
+
+                starting_pos = Some(pos);
+            }
         }
     }
+
+    split.ends_in_quoted_field = inner.is_in_quoted_field();
+
+    if split.ends_in_quoted_field {
+        split.pending = starting_pos;
+    }
+
+    split
 }
 
 /// An already configured CSV reader working on a slice of bytes or on a memory
@@ -76,11 +276,21 @@ impl TotalReaderBuilder {
 /// [`TotalReaderBuilder`].
 pub struct TotalReader<'b> {
     inner: CoreReader,
+    #[cfg(feature = "std")]
+    dialect: Dialect,
     bytes: &'b [u8],
     pos: usize,
     headers: ByteRecord,
+    skip_lines: SkipLines,
+    preamble: &'b [u8],
+    had_bom: bool,
     has_read: bool,
     has_headers: bool,
+    // NOTE: lines are counted lazily, `line` being the physical line of
+    // `line_pos` in the byte slice
+    line: u64,
+    line_pos: usize,
+    index: u64,
 }
 
 impl<'b> TotalReader<'b> {
@@ -99,6 +309,14 @@ impl<'b> TotalReader<'b> {
         // Trimming BOM
         let bom_len = trim_bom(self.bytes);
         self.pos += bom_len;
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        let preamble_len = self
+            .skip_lines
+            .skip_bytes(&self.bytes[self.pos..], &self.inner);
+        self.preamble = &self.bytes[self.pos..self.pos + preamble_len];
+        self.pos += preamble_len;
 
         // Reading headers
         let mut headers = ByteRecord::new();
@@ -106,7 +324,8 @@ impl<'b> TotalReader<'b> {
         let has_data = self.read_byte_record_impl(&mut headers);
 
         if has_data && !self.has_headers {
-            self.pos = bom_len;
+            self.pos = bom_len + preamble_len;
+            self.index = 0;
         }
 
         self.headers = headers;
@@ -122,7 +341,25 @@ impl<'b> TotalReader<'b> {
         &self.headers
     }
 
-    /// Count the total number of records.
+    /// Return the raw preamble lines that were skipped before the header row,
+    /// terminators included.
+    #[inline]
+    pub fn preamble(&mut self) -> &'b [u8] {
+        self.on_first_read();
+
+        self.preamble
+    }
+
+    /// Return whether the input started with a UTF-8 BOM, which was trimmed.
+    #[inline]
+    pub fn input_had_bom(&mut self) -> bool {
+        self.on_first_read();
+
+        self.had_bom
+    }
+
+    /// Count the total number of records, not including the headers if the
+    /// reader was configured to have some.
     pub fn count_records(&mut self) -> u64 {
         use ReadResult::*;
 
@@ -137,14 +374,166 @@ impl<'b> TotalReader<'b> {
 
             match result {
                 End => break,
-                InputEmpty | Cr | Lf => continue,
+                InputEmpty | Cr | Lf | Comment => continue,
+                MalformedQuote | UnterminatedQuote => {
+                    unreachable!("total reader cannot be strict")
+                }
                 Record => {
                     count += 1;
                 }
             };
         }
 
-        count.saturating_sub(if self.has_headers { 1 } else { 0 })
+        self.index += count;
+
+        count
+    }
+
+    // NOTE: chunks are cut right after a newline, so that their first byte
+    // either starts a record or stands within a quoted field.
+    #[cfg(feature = "std")]
+    fn chunks(&self, threads: usize) -> Vec<Range<usize>> {
+        let end = self.bytes.len();
+        let chunk_size = (end - self.pos).div_ceil(threads.max(1)).max(1);
+
+        let mut chunks = Vec::with_capacity(threads);
+        let mut start = self.pos;
+
+        loop {
+            let target = start + chunk_size;
+
+            let chunk_end = if target >= end {
+                end
+            } else {
+                memchr(self.inner.newline(), &self.bytes[target..])
+                    .map(|offset| target + offset + 1)
+                    .unwrap_or(end)
+            };
+
+            chunks.push(start..chunk_end);
+
+            if chunk_end == end {
+                break;
+            }
+
+            start = chunk_end;
+        }
+
+        chunks
+    }
+
+    #[cfg(feature = "std")]
+    fn par_split_chunks(&mut self, threads: usize, collect: bool) -> Vec<ChunkSplit> {
+        self.on_first_read();
+
+        let chunks = self.chunks(threads);
+        let last = chunks.len() - 1;
+        let dialect = &self.dialect;
+        let bytes = self.bytes;
+
+        // NOTE: each chunk but the first one is split twice: once assuming it
+        // starts a record and once assuming it starts within a quoted field.
+        // The correct hypothesis is only known once preceding chunks are
+        // resolved, which is done sequentially afterwards.
+        let splits: Vec<(ChunkSplit, Option<ChunkSplit>)> = thread::scope(|scope| {
+            let handles = chunks
+                .into_iter()
+                .enumerate()
+                .map(|(i, range)| {
+                    scope.spawn(move || {
+                        let is_last = i == last;
+
+                        let quoted = (i > 0 && dialect.quoting).then(|| {
+                            split_chunk(dialect, bytes, range.clone(), true, is_last, collect)
+                        });
+
+                        (
+                            split_chunk(dialect, bytes, range, false, is_last, collect),
+                            quoted,
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        self.pos = bytes.len();
+
+        let mut in_quoted_field = false;
+
+        splits
+            .into_iter()
+            .map(|(unquoted, quoted)| {
+                let split = if in_quoted_field {
+                    quoted.unwrap()
+                } else {
+                    unquoted
+                };
+
+                in_quoted_field = split.ends_in_quoted_field;
+
+                split
+            })
+            .collect()
+    }
+
+    /// Split the remaining records using the given number of threads, and
+    /// return their byte slices, terminators included, in order.
+    ///
+    /// The input is cut into chunks, each one being split in parallel under
+    /// both the hypothesis that it starts a record and that it starts within a
+    /// quoted field, before the correct hypotheses are resolved sequentially.
+    /// Records are therefore exactly the ones [`Self::split_record`] would
+    /// return, at the cost of splitting most of the input twice.
+    #[cfg(feature = "std")]
+    pub fn par_split_records(&mut self, threads: usize) -> Vec<&'b [u8]> {
+        let bytes = self.bytes;
+        let splits = self.par_split_chunks(threads, true);
+
+        let mut records = Vec::with_capacity(splits.iter().map(|split| split.records.len()).sum());
+        let mut pending: Option<usize> = None;
+
+        for split in splits {
+            for (start, end) in split.records {
+                let start = start.or_else(|| pending.take()).unwrap();
+
+                records.push(&bytes[start..end]);
+            }
+
+            if !split.ends_in_quoted_field {
+                pending = None;
+            } else if split.pending.is_some() {
+                pending = split.pending;
+            }
+        }
+
+        self.index += records.len() as u64;
+
+        records
+    }
+
+    /// Count the remaining records using the given number of threads.
+    ///
+    /// See [`Self::par_split_records`] for more detail.
+    #[cfg(feature = "std")]
+    pub fn par_count_records(&mut self, threads: usize) -> u64 {
+        let count = self
+            .par_split_chunks(threads, false)
+            .into_iter()
+            .map(|split| split.count)
+            .sum();
+
+        self.index += count;
+
+        count
     }
 
     /// Attempt to split the next CSV record and return an optional reference to
@@ -156,7 +545,7 @@ impl<'b> TotalReader<'b> {
 
         self.on_first_read();
 
-        let starting_pos = self.pos;
+        let mut starting_pos = self.pos;
 
         loop {
             let (result, pos) = self.inner.split_record(&self.bytes[self.pos..]);
@@ -165,8 +554,17 @@ impl<'b> TotalReader<'b> {
 
             match result {
                 End => return None,
-                InputEmpty | Cr | Lf => continue,
-                Record => return Some(&self.bytes[starting_pos..self.pos]),
+                InputEmpty => continue,
+                Cr | Lf | Comment => {
+                    starting_pos = self.pos;
+                }
+                MalformedQuote | UnterminatedQuote => {
+                    unreachable!("total reader cannot be strict")
+                }
+                Record => {
+                    self.index += 1;
+                    return Some(&self.bytes[starting_pos..self.pos]);
+                }
             }
         }
     }
@@ -177,6 +575,7 @@ impl<'b> TotalReader<'b> {
         record.clear();
 
         let mut record_builder = ByteRecordBuilder::wrap(record);
+        let mut starting_pos = self.pos;
 
         loop {
             let (result, pos) = self
@@ -189,16 +588,43 @@ impl<'b> TotalReader<'b> {
                 End => {
                     return false;
                 }
-                Cr | Lf | InputEmpty => {
+                InputEmpty => {
                     continue;
                 }
+                Cr | Lf | Comment => {
+                    starting_pos = self.pos;
+                }
+                MalformedQuote | UnterminatedQuote => {
+                    unreachable!("total reader cannot be strict")
+                }
                 Record => {
+                    let position = self.position_of(starting_pos);
+                    record.set_position(Some(position));
+
+                    self.index += 1;
                     return true;
                 }
             };
         }
     }
 
+    fn position_of(&mut self, pos: usize) -> Position {
+        // NOTE: the same records might be read twice when there are no headers
+        if pos >= self.line_pos {
+            self.line += count_lines(&self.bytes[self.line_pos..pos], self.inner.newline());
+        } else {
+            self.line -= count_lines(&self.bytes[pos..self.line_pos], self.inner.newline());
+        }
+
+        self.line_pos = pos;
+
+        Position {
+            byte: pos as u64,
+            line: self.line,
+            record: self.index,
+        }
+    }
+
     /// Attempt to read the next CSV record into a pre-allocated [`ByteRecord`].
     ///
     /// Returns a boolean indicating whether a record was actually read or if we
@@ -249,6 +675,9 @@ impl Iterator for ByteRecordsIter<'_, '_> {
 mod tests {
     use super::*;
 
+    use alloc::vec;
+    use alloc::vec::Vec;
+
     impl<'b> TotalReader<'b> {
         fn from_bytes_no_headers(bytes: &'b [u8]) -> Self {
             TotalReaderBuilder::new()
@@ -280,7 +709,7 @@ mod tests {
         assert_eq!(count_records(""), 0);
 
         // Single cells with various empty lines
-        let tests = vec![
+        let tests = [
             "name\njohn\nlucy",
             "name\njohn\nlucy\n",
             "name\n\njohn\r\nlucy\n",
@@ -299,6 +728,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_count_records_with_headers() {
+        assert_eq!(TotalReader::from_bytes(b"").count_records(), 0);
+        assert_eq!(TotalReader::from_bytes(b"name\n").count_records(), 0);
+        assert_eq!(TotalReader::from_bytes(b"name\njohn").count_records(), 1);
+        assert_eq!(
+            TotalReader::from_bytes(b"name\njohn\nlucy\n").count_records(),
+            2
+        );
+
+        let mut reader = TotalReader::from_bytes(b"name\njohn\nlucy\n");
+        assert_eq!(reader.byte_headers(), &brec!["name"]);
+        assert_eq!(reader.count_records(), 2);
+
+        let mut reader = TotalReaderBuilder::new()
+            .has_headers(false)
+            .from_bytes(b"name\njohn\nlucy\n");
+        assert_eq!(reader.count_records(), 3);
+    }
+
+    #[test]
+    fn test_comment() {
+        let data = b"# metadata\nname,surname\n# john,landis\nlucy,rose\n\n#\nbeatrice,babka\n";
+
+        let mut reader = TotalReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_bytes(data);
+
+        assert_eq!(reader.count_records(), 2);
+
+        let mut reader = TotalReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_bytes(data);
+
+        assert_eq!(reader.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(reader.split_record(), Some(b"lucy,rose\n".as_slice()));
+        assert_eq!(reader.split_record(), Some(b"beatrice,babka\n".as_slice()));
+        assert_eq!(reader.split_record(), None);
+    }
+
+    #[test]
+    fn test_multi_byte_delimiter() {
+        let data = b"name||surname\njohn||\"la||ndis\"\nlucy|||rose\n";
+
+        let mut reader = TotalReaderBuilder::new()
+            .delimiter_bytes(b"||")
+            .from_bytes(data);
+
+        assert_eq!(reader.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(
+            reader.byte_records().collect::<Vec<_>>(),
+            vec![brec!["john", "la||ndis"], brec!["lucy", "|rose"]]
+        );
+    }
+
+    #[test]
+    fn test_lenient_quotes() {
+        fn csv_crate_records(data: &str) -> Vec<ByteRecord> {
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(data.as_bytes())
+                .byte_records()
+                .map(|record| {
+                    let mut converted = ByteRecord::new();
+
+                    for cell in record.unwrap().iter() {
+                        converted.push_field(cell);
+                    }
+
+                    converted
+                })
+                .collect()
+        }
+
+        let data = "name,surname\njoh\"n,landis\nbéatrice,babka\n\"quoted, ok\",5\"\nfoo,\"bar\"\"\"\n5'11\",x\"y\n\"ab\"cd,\"x\"y\"z\"\n\"a\",\n";
+
+        let mut reader = TotalReaderBuilder::new()
+            .has_headers(false)
+            .lenient_quotes(true)
+            .from_bytes(data.as_bytes());
+
+        assert_eq!(
+            reader.byte_records().collect::<Vec<_>>(),
+            csv_crate_records(data)
+        );
+
+        let mut reader = TotalReaderBuilder::new()
+            .has_headers(false)
+            .lenient_quotes(true)
+            .from_bytes(data.as_bytes());
+
+        assert_eq!(reader.count_records(), 8);
+    }
+
     #[test]
     fn test_byte_headers() {
         let data = b"name,surname\njohn,dandy";
@@ -345,4 +868,111 @@ mod tests {
         assert_eq!(reader.byte_headers(), &brec![]);
         assert!(reader.byte_records().next().is_none());
     }
+
+    #[test]
+    fn test_skip_lines() {
+        let data = b"sep=,\r\n\"metadata\nname,surname\njohn,landis\nlucy,rose\n";
+
+        let mut reader = TotalReaderBuilder::new().skip_lines(2).from_bytes(data);
+
+        assert_eq!(reader.preamble(), b"sep=,\r\n\"metadata\n");
+        assert_eq!(reader.byte_headers(), &brec!["name", "surname"]);
+        assert_eq!(reader.count_records(), 2);
+
+        let mut reader = TotalReaderBuilder::new()
+            .has_headers(false)
+            .skip_lines_while(|line| !line.starts_with(b"name"))
+            .from_bytes(data);
+
+        assert_eq!(reader.preamble(), b"sep=,\r\n\"metadata\n");
+        assert_eq!(
+            reader.byte_records().collect::<Vec<_>>(),
+            vec![
+                brec!["name", "surname"],
+                brec!["john", "landis"],
+                brec!["lucy", "rose"]
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_par_split_records() {
+        let mut data = String::from("name,surname,notes\r\n");
+
+        for i in 0..200 {
+            match i % 5 {
+                0 => data.push_str(&format!("john{},\"landy\n\"\"the\"\"\n\",\"\"\r\n", i)),
+                1 => data.push_str("\n# comment, \"with quote\n"),
+                2 => data.push_str(&format!("\"lucy\",\"\n\n\n{}\n\",rose\n", i)),
+                _ => data.push_str(&format!("beatrice,babka,{}\n", i)),
+            }
+        }
+
+        data.push_str("last,\"record");
+
+        for has_headers in [true, false] {
+            for quoting in [true, false] {
+                let mut builder = TotalReaderBuilder::new();
+                builder
+                    .has_headers(has_headers)
+                    .quoting(quoting)
+                    .comment(Some(b'#'));
+
+                let mut reader = builder.from_bytes(data.as_bytes());
+                let mut expected = Vec::new();
+
+                while let Some(record) = reader.split_record() {
+                    expected.push(record.to_vec());
+                }
+
+                for threads in [1, 2, 3, 4, 7, 32, 1000] {
+                    let mut reader = builder.from_bytes(data.as_bytes());
+
+                    assert_eq!(reader.par_split_records(threads), expected);
+                    assert!(reader.split_record().is_none());
+
+                    let mut reader = builder.from_bytes(data.as_bytes());
+
+                    assert_eq!(reader.par_count_records(threads), expected.len() as u64);
+                }
+            }
+        }
+
+        assert!(TotalReader::from_bytes(b"").par_split_records(4).is_empty());
+        assert_eq!(TotalReader::from_bytes(b"name\n").par_count_records(4), 0);
+    }
+
+    #[test]
+    fn test_record_position() {
+        let data = b"sep=,\nname,notes\n\"john\",\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\nc\"\nlast,one";
+
+        let pos = |byte, line, record| Position { byte, line, record };
+
+        let mut reader = TotalReaderBuilder::new()
+            .skip_lines(1)
+            .comment(Some(b'#'))
+            .from_bytes(data);
+
+        assert_eq!(reader.byte_headers().position(), Some(&pos(6, 2, 0)));
+        assert_eq!(
+            reader
+                .byte_records()
+                .map(|record| *record.position().unwrap())
+                .collect::<Vec<_>>(),
+            vec![pos(17, 3, 1), pos(48, 7, 2), pos(59, 8, 3), pos(71, 11, 4)]
+        );
+
+        let mut reader = TotalReaderBuilder::new()
+            .has_headers(false)
+            .from_bytes(b"\njohn\n\"lucy\nrose\"\nbob");
+
+        assert_eq!(
+            reader
+                .byte_records()
+                .map(|record| *record.position().unwrap())
+                .collect::<Vec<_>>(),
+            vec![pos(1, 2, 0), pos(6, 3, 1), pos(18, 5, 2)]
+        );
+    }
 }
@@ -0,0 +1,299 @@
+use crate::buffer::{Buffered, Positioned, Scratch};
+use crate::core::{CoreReader, ReadResult};
+use crate::error::{self, Error, ErrorKind};
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::records::ByteRecordBuilder;
+use crate::utils::count_lines;
+
+/// What a reader must do once a [`Tracker`] handled the result of its
+/// [`CoreReader`].
+pub(crate) enum Step {
+    /// Keep on feeding the core reader.
+    Continue,
+    /// A whole record was read.
+    Record,
+    /// The stream is exhausted.
+    End,
+}
+
+/// Location of a reader in its stream, along with the limits it enforces.
+///
+/// It handles the results of a [`CoreReader`] using the buffer that was given
+/// to it, without performing any IO, so that the sync & async readers share
+/// the body of their read loops.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tracker {
+    pub(crate) limits: Limits,
+    // NOTE: physical line of the current position in the stream
+    pub(crate) line: u64,
+    pub(crate) index: u64,
+    // NOTE: position of the record being read, past any skipped line
+    start: Position,
+}
+
+impl Tracker {
+    pub(crate) fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            line: 1,
+            index: 0,
+            start: Position::new(),
+        }
+    }
+
+    /// Returns the position of the next record, the stream standing at byte
+    /// offset `byte`.
+    #[inline(always)]
+    pub(crate) fn position_at(&self, byte: u64) -> Position {
+        Position {
+            byte,
+            line: self.line,
+            record: self.index,
+        }
+    }
+
+    /// Move to the given position, typically after seeking the stream.
+    #[inline]
+    pub(crate) fn seek(&mut self, pos: Position) {
+        self.line = pos.line;
+        self.index = pos.record;
+    }
+
+    /// Start reading a record at byte offset `byte`.
+    #[inline(always)]
+    pub(crate) fn start(&mut self, byte: u64) {
+        self.start = self.position_at(byte);
+    }
+
+    /// Returns the position of the record being read, or of the last one if
+    /// it was fully read.
+    #[inline(always)]
+    pub(crate) fn record_position(&self) -> Position {
+        self.start
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> (u64, u64) {
+        (self.start.byte, self.start.record)
+    }
+
+    // NOTE: the malformed record is skipped, but still counted
+    #[cold]
+    fn malformed_quote(&mut self, byte: u64) -> Error {
+        self.index += 1;
+
+        Error::new(ErrorKind::MalformedQuote {
+            pos: Some((byte, self.start.record)),
+        })
+    }
+
+    #[cold]
+    fn unterminated_quote(&self) -> Error {
+        Error::new(ErrorKind::UnterminatedQuote {
+            pos: Some(self.pos()),
+        })
+    }
+
+    #[inline]
+    fn check_field_count(&self, expected_len: Option<usize>, len: usize) -> error::Result<()> {
+        match expected_len {
+            Some(expected_len) if len != expected_len => {
+                Err(Error::new(ErrorKind::UnequalLengths {
+                    expected_len,
+                    len,
+                    pos: Some(self.pos()),
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // NOTE: the `pos` bytes read by the core reader are consumed right away,
+    // which is fine as long as the record itself is not needed as a slice.
+    fn consume_step<B: Buffered>(
+        &mut self,
+        buffer: &mut Positioned<B>,
+        (result, pos): (ReadResult, usize),
+        fields: Option<usize>,
+    ) -> error::Result<Step> {
+        use ReadResult::*;
+
+        buffer.consume(pos);
+
+        match result {
+            End => Ok(Step::End),
+            Cr | Lf | Comment => {
+                self.start(buffer.position());
+                Ok(Step::Continue)
+            }
+            MalformedQuote => Err(self.malformed_quote(buffer.position())),
+            UnterminatedQuote => Err(self.unterminated_quote()),
+            InputEmpty | Record => {
+                self.limits
+                    .check_record_span(self.pos(), buffer.position())?;
+
+                if let Some(fields) = fields {
+                    self.limits.check_fields(fields, self.pos())?;
+                }
+
+                if matches!(result, InputEmpty) {
+                    return Ok(Step::Continue);
+                }
+
+                self.index += 1;
+
+                Ok(Step::Record)
+            }
+        }
+    }
+
+    /// Handle the result of [`CoreReader::read_record`], given the buffer it
+    /// was fed with. `expected_len` is the number of fields records must have,
+    /// if it must be enforced.
+    pub(crate) fn read_step<B: Buffered>(
+        &mut self,
+        buffer: &mut Positioned<B>,
+        read: (ReadResult, usize),
+        core: &mut CoreReader,
+        record_builder: &mut ByteRecordBuilder,
+        expected_len: Option<usize>,
+    ) -> error::Result<Step> {
+        self.line += core.lines_read(&buffer.buffer()[..read.1]);
+
+        let step = self.consume_step(buffer, read, Some(record_builder.fields()))?;
+
+        if let Step::Record = step {
+            record_builder.set_position(self.start);
+            self.check_field_count(expected_len, record_builder.fields())?;
+        }
+
+        Ok(step)
+    }
+
+    /// Handle the result of [`CoreReader::split_record`] when only counting
+    /// records, given the buffer it was fed with.
+    ///
+    /// Lines are not counted, so that counting remains as fast as possible,
+    /// meaning `line` is not reliable anymore afterwards.
+    pub(crate) fn count_step<B: Buffered>(
+        &mut self,
+        buffer: &mut Positioned<B>,
+        read: (ReadResult, usize),
+    ) -> error::Result<Step> {
+        let step = self.consume_step(buffer, read, None)?;
+
+        if let Step::Record = step {
+            self.start(buffer.position());
+        }
+
+        Ok(step)
+    }
+
+    /// Handle the result of [`CoreReader::split_record`], given the buffer it
+    /// was fed with. Once a record is read, it can be found in
+    /// [`Scratch::flushed`].
+    pub(crate) fn split_step<B: Buffered>(
+        &mut self,
+        buffer: &mut Scratch<B>,
+        (result, pos): (ReadResult, usize),
+        core: &mut CoreReader,
+    ) -> error::Result<Step> {
+        use ReadResult::*;
+
+        match result {
+            End => {
+                buffer.consume(pos);
+                Ok(Step::End)
+            }
+            Cr | Lf | Comment => {
+                self.line += core.lines_read(&buffer.buffer()[..pos]);
+                buffer.consume(pos);
+
+                self.start(buffer.position());
+                Ok(Step::Continue)
+            }
+            InputEmpty => {
+                buffer.save();
+
+                self.limits
+                    .check_record_size(buffer.saved().len(), self.pos())?;
+
+                Ok(Step::Continue)
+            }
+            MalformedQuote => {
+                // NOTE: the core reader skips the rest of the line from there
+                self.line += count_lines(buffer.saved(), core.newline())
+                    + count_lines(&buffer.buffer()[..pos], core.newline());
+                buffer.consume(pos);
+
+                Err(self.malformed_quote(buffer.position()))
+            }
+            UnterminatedQuote => Err(self.unterminated_quote()),
+            Record => {
+                let size = buffer.saved().len() + pos;
+
+                self.limits.check_record_size(size, self.pos())?;
+
+                self.index += 1;
+
+                buffer.flush(pos);
+                self.line += core.lines_read(buffer.flushed());
+
+                Ok(Step::Record)
+            }
+        }
+    }
+
+    /// Handle the result of [`CoreReader::split_record_and_find_separators`],
+    /// given the buffer it was fed with and the separators found so far. Once
+    /// a record is read, it can be found in [`Scratch::flushed`] and its
+    /// separators have been verified.
+    pub(crate) fn zero_copy_step<B: Buffered>(
+        &mut self,
+        buffer: &mut Scratch<B>,
+        (result, pos): (ReadResult, usize),
+        core: &mut CoreReader,
+        seps: &mut Vec<usize>,
+        expected_len: Option<usize>,
+    ) -> error::Result<Step> {
+        use ReadResult::*;
+
+        match result {
+            InputEmpty => {
+                buffer.save();
+
+                self.limits
+                    .check_record_size(buffer.saved().len(), self.pos())?;
+
+                // NOTE: separators cannot be counted before being verified
+                if !core.delimiter.is_multi_byte() {
+                    self.limits.check_fields(seps.len() + 1, self.pos())?;
+                }
+
+                Ok(Step::Continue)
+            }
+            Record => {
+                let size = buffer.saved().len() + pos;
+
+                self.limits.check_record_size(size, self.pos())?;
+
+                buffer.flush(pos);
+                self.line += core.lines_read(buffer.flushed());
+
+                if core.delimiter.is_multi_byte() {
+                    core.delimiter
+                        .filter_separators(core.trim_terminator(buffer.flushed()), seps);
+                }
+
+                self.limits.check_fields(seps.len() + 1, self.pos())?;
+
+                self.index += 1;
+                self.check_field_count(expected_len, seps.len() + 1)?;
+
+                Ok(Step::Record)
+            }
+            _ => self.split_step(buffer, (result, pos), core),
+        }
+    }
+}
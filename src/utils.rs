@@ -1,7 +1,11 @@
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
 use std::io::{self, Read, Seek, SeekFrom, Write};
 
-use memchr::memchr;
+use memchr::{memchr, memchr2};
 
 #[inline]
 pub fn trim_trailing_crlf(slice: &[u8]) -> &[u8] {
@@ -15,6 +19,13 @@ pub fn trim_trailing_crlf(slice: &[u8]) -> &[u8] {
     &slice[..len]
 }
 
+/// Count the physical lines, i.e. record terminator bytes, contained in the
+/// given slice.
+#[inline]
+pub fn count_lines(slice: &[u8], newline: u8) -> u64 {
+    memchr::memchr_iter(newline, slice).count() as u64
+}
+
 #[inline(always)]
 pub fn trim_bom(slice: &[u8]) -> usize {
     if slice.len() >= 3 && &slice[..3] == b"\xef\xbb\xbf" {
@@ -101,12 +112,120 @@ pub fn unescape_to(cell: &[u8], quote: u8, out: &mut Vec<u8>) {
     out.extend_from_slice(&cell[pos..]);
 }
 
+/// Unescape a potentially escaped but unquoted (no leading/trailing quotes) CSV
+/// cell, given an optional escape char and whether doubled quotes should be
+/// understood as escaped quotes.
+///
+/// When an escape char is given, it will be dropped and the byte following it
+/// will be kept as-is.
+///
+/// When quotes are not doubled, the first quote that is not escaped is
+/// understood as closing the quoted part of the cell, the way the csv crate
+/// reads it: this quote is dropped, and the rest of the cell is kept as-is,
+/// followed by the cell's actual closing quote.
+///
+/// Calling `unescape_with(cell, quote, None, true)` is the same as calling
+/// [`unescape`].
+pub fn unescape_with(
+    cell: &[u8],
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+) -> Cow<'_, [u8]> {
+    match escape {
+        None if double_quote => unescape(cell, quote),
+        None => match memchr(quote, cell) {
+            None => Cow::Borrowed(cell),
+            Some(i) => {
+                let mut output = Vec::with_capacity(cell.len());
+                close_quoted_to(cell, i, quote, &mut output);
+
+                Cow::Owned(output)
+            }
+        },
+        Some(escape) => {
+            if memchr2(quote, escape, cell).is_none() {
+                return Cow::Borrowed(cell);
+            }
+
+            let mut output = Vec::with_capacity(cell.len());
+            unescape_escaped_to(cell, quote, escape, double_quote, &mut output);
+
+            Cow::Owned(output)
+        }
+    }
+}
+
+pub fn unescape_with_to(
+    cell: &[u8],
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+    out: &mut Vec<u8>,
+) {
+    match escape {
+        None if double_quote => unescape_to(cell, quote, out),
+        None => match memchr(quote, cell) {
+            None => out.extend_from_slice(cell),
+            Some(i) => close_quoted_to(cell, i, quote, out),
+        },
+        Some(escape) => unescape_escaped_to(cell, quote, escape, double_quote, out),
+    }
+}
+
+fn unescape_escaped_to(cell: &[u8], quote: u8, escape: u8, double_quote: bool, out: &mut Vec<u8>) {
+    let len = cell.len();
+    let mut pos: usize = 0;
+
+    while let Some(offset) = memchr2(quote, escape, &cell[pos..]) {
+        let i = pos + offset;
+
+        out.extend_from_slice(&cell[pos..i]);
+
+        let byte = cell[i];
+
+        // NOTE: escape char takes precedence, in case it is the same as the quote
+        if byte == escape {
+            if i + 1 < len {
+                out.push(cell[i + 1]);
+                pos = i + 2;
+            } else {
+                out.push(byte);
+                pos = i + 1;
+            }
+        } else if !double_quote {
+            close_quoted_to(&cell[i..], 0, quote, out);
+            return;
+        } else {
+            out.push(byte);
+
+            if i + 1 < len && cell[i + 1] == quote {
+                pos = i + 2;
+            } else {
+                pos = i + 1;
+            }
+        }
+    }
+
+    out.extend_from_slice(&cell[pos..]);
+}
+
+// NOTE: the quote at `i` closes the quoted part of the cell, the rest being
+// literal, and the cell's own closing quote, that was stripped, must be restored
+fn close_quoted_to(cell: &[u8], i: usize, quote: u8, out: &mut Vec<u8>) {
+    out.extend_from_slice(&cell[..i]);
+    out.extend_from_slice(&cell[i + 1..]);
+    out.push(quote);
+}
+
+#[cfg(feature = "std")]
 pub struct ReverseReader<R> {
     input: R,
     offset: u64,
     ptr: u64,
 }
 
+#[cfg(feature = "std")]
 impl<R: Seek + Read> ReverseReader<R> {
     pub fn new(input: R, filesize: u64, offset: u64) -> Self {
         Self {
@@ -117,6 +236,7 @@ impl<R: Seek + Read> ReverseReader<R> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: Seek + Read> Read for ReverseReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let buff_size = buf.len() as u64;
@@ -154,7 +274,8 @@ impl<R: Seek + Read> Read for ReverseReader<R> {
 /// restricting everything else and that can be used to maintain some data
 /// structure invariants.
 ///
-/// It also implements [`Write`], as a convenience.
+/// It also implements [`std::io::Write`], when the `std` feature is enabled,
+/// and [`core::fmt::Write`], as a convenience.
 pub struct AppendOnlyView<'v, T> {
     inner: &'v mut Vec<T>,
 }
@@ -195,6 +316,7 @@ impl<'v, T> Extend<T> for AppendOnlyView<'v, T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'v, T> Write for AppendOnlyView<'v, T>
 where
     Vec<T>: Write,
@@ -215,7 +337,7 @@ where
     }
 
     #[inline(always)]
-    fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> io::Result<()> {
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
         self.inner.write_fmt(fmt)
     }
 
@@ -225,6 +347,14 @@ where
     }
 }
 
+impl fmt::Write for AppendOnlyView<'_, u8> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +399,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unescape_with() {
+        assert_eq!(
+            unescape_with(b"test", b'"', Some(b'\\'), false),
+            Cow::Borrowed(b"test")
+        );
+        assert_eq!(
+            unescape_with(b"\\\"hello\\\"", b'"', Some(b'\\'), false),
+            Cow::<[u8]>::Owned(b"\"hello\"".to_vec())
+        );
+        assert_eq!(
+            unescape_with(b"back\\\\slash", b'"', Some(b'\\'), false),
+            Cow::<[u8]>::Owned(b"back\\slash".to_vec())
+        );
+        assert_eq!(
+            unescape_with(b"\"\"both\\\"", b'"', Some(b'\\'), true),
+            Cow::<[u8]>::Owned(b"\"both\"".to_vec())
+        );
+        assert_eq!(
+            unescape_with(b"hello", b'"', None, false),
+            Cow::Borrowed(b"hello")
+        );
+        assert_eq!(
+            unescape_with(b"x\"\"y", b'"', None, false),
+            Cow::<[u8]>::Owned(b"x\"y\"".to_vec())
+        );
+        assert_eq!(
+            unescape_with(b"x\"\"y\\\"", b'"', Some(b'\\'), false),
+            Cow::<[u8]>::Owned(b"x\"y\\\"\"".to_vec())
+        );
+        assert_eq!(
+            unescape_with(b"\"\"hello\"\"", b'"', None, true),
+            Cow::<[u8]>::Owned(b"\"hello\"".to_vec())
+        );
+
+        // It should remain safe with incomplete/invalid data
+        assert_eq!(
+            unescape_with(b"goettigen\\", b'"', Some(b'\\'), false),
+            Cow::<[u8]>::Owned(b"goettigen\\".to_vec())
+        );
+    }
+
     #[test]
     fn test_unescape_to() {
         let mut scratch = Vec::new();
@@ -1,27 +1,42 @@
 use std::io::{self, BufWriter, IntoInnerError, Write};
 
-use memchr::memchr;
+use memchr::{memchr, memchr2};
 
+use crate::dialect::{Delimiter, Quoting, Terminator};
 use crate::error::{self, Error, ErrorKind};
 use crate::records::{ByteRecord, ZeroCopyByteRecord};
+#[cfg(feature = "serde")]
+use crate::serializer::serialize_record;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 
 /// Builds a [`Writer`] with given configuration.
 pub struct WriterBuilder {
-    delimiter: u8,
+    delimiter: Delimiter,
     quote: u8,
-    buffer_capacity: usize,
+    quoting: bool,
+    escape: Option<u8>,
+    double_quote: bool,
+    pub(crate) buffer_capacity: usize,
     flexible: bool,
-    crlf: bool,
+    terminator: Terminator,
+    #[cfg(feature = "serde")]
+    has_headers: bool,
 }
 
 impl Default for WriterBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
+            delimiter: Delimiter::from(b','),
             quote: b'"',
+            quoting: true,
+            escape: None,
+            double_quote: true,
             buffer_capacity: 8192,
             flexible: false,
-            crlf: false,
+            terminator: Terminator::Any(b'\n'),
+            #[cfg(feature = "serde")]
+            has_headers: true,
         }
     }
 }
@@ -41,11 +56,25 @@ impl WriterBuilder {
 
     /// Set the delimiter to be used by the created [`Writer`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`Writer`].
+    ///
+    /// Cells containing the first byte of the delimiter will be quoted.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -59,9 +88,62 @@ impl WriterBuilder {
         self
     }
 
+    /// Indicate whether the created [`Writer`] should quote cells when
+    /// needed.
+    ///
+    /// When disabled, quote chars will be written as-is, and attempting to
+    /// write a cell containing the delimiter or the record terminator, i.e.
+    /// either CR or LF when using CRLF, will return an
+    /// [`ErrorKind::UnquotableCell`] error, since it cannot be written without
+    /// corrupting the data. The same goes for a record made of a single empty
+    /// cell, which could only be written as an empty line. In both cases,
+    /// nothing from the offending record will be written.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`Writer`].
+    ///
+    /// When given, escape chars found in quoted fields will be escaped
+    /// themselves, and quotes will also be escaped using this char instead of
+    /// being doubled if [`Self::double_quote`] is set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Indicate whether the created [`Writer`] should escape quotes by
+    /// doubling them.
+    ///
+    /// When set to `false`, quotes will be escaped using the escape char
+    /// instead, if one was given (see [`Self::escape`]).
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.double_quote = yes;
+        self
+    }
+
     /// Indicate that the created [`Writer`] should use CRLF newlines.
     pub fn crlf_newlines(&mut self, yes: bool) -> &mut Self {
-        self.crlf = yes;
+        self.terminator = if yes {
+            Terminator::CRLF
+        } else {
+            Terminator::Any(b'\n')
+        };
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`Writer`].
+    ///
+    /// Will default to `Terminator::Any(b'\n')`, i.e. LF newlines.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.terminator = terminator;
         self
     }
 
@@ -81,60 +163,84 @@ impl WriterBuilder {
         self
     }
 
-    /// Create a new [`Writer`] using the provided writer implementing
-    /// [`std::io::Write`].
-    pub fn from_writer<W: Write>(&self, writer: W) -> Writer<W> {
+    /// Indicate whether the created [`Writer`] should write a header row,
+    /// made of struct field names or map keys, before the first record
+    /// written using [`Writer::serialize`].
+    ///
+    /// Will default to `true`.
+    #[cfg(feature = "serde")]
+    pub fn has_headers(&mut self, yes: bool) -> &mut Self {
+        self.has_headers = yes;
+        self
+    }
+
+    pub(crate) fn encoder(&self) -> RecordEncoder {
         let mut must_quote = [false; 256];
-        must_quote[b'\r' as usize] = true;
-        must_quote[b'\n' as usize] = true;
-        must_quote[self.delimiter as usize] = true;
-        must_quote[self.quote as usize] = true;
 
-        Writer {
+        // NOTE: without quoting, only bytes actually breaking the record must
+        // be flagged since cells containing them cannot be written at all.
+        if self.quoting {
+            must_quote[b'\r' as usize] = true;
+            must_quote[b'\n' as usize] = true;
+            must_quote[self.quote as usize] = true;
+        } else if self.terminator == Terminator::CRLF {
+            must_quote[b'\r' as usize] = true;
+        }
+
+        must_quote[self.terminator.byte() as usize] = true;
+        must_quote[self.delimiter.first() as usize] = true;
+
+        RecordEncoder {
             delimiter: self.delimiter,
-            quote: self.quote,
-            line_terminator: if self.crlf { b"\r\n" } else { b"\n" },
-            buf_writer: BufWriter::with_capacity(self.buffer_capacity, writer),
+            terminator: self.terminator,
+            quoting: Quoting {
+                enabled: self.quoting,
+                quote: self.quote,
+                escape: self.escape,
+                double_quote: self.double_quote,
+                lenient: false,
+            },
+            line_terminator: match self.terminator {
+                Terminator::CRLF => b"\r\n".to_vec(),
+                Terminator::Any(byte) => vec![byte],
+            },
             flexible: self.flexible,
             field_count: None,
             must_quote,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Create a new [`Writer`] using the provided writer implementing
+    /// [`std::io::Write`].
+    pub fn from_writer<W: Write>(&self, writer: W) -> Writer<W> {
+        Writer {
+            encoder: self.encoder(),
+            buf_writer: BufWriter::with_capacity(self.buffer_capacity, writer),
+            #[cfg(feature = "serde")]
+            record: ByteRecord::new(),
+            #[cfg(feature = "serde")]
+            must_write_headers: self.has_headers,
         }
     }
 }
 
-/// An already configured CSV writer.
-///
-/// # Configuration
-///
-/// To configure a [`Writer`], if you need a custom delimiter for instance of if
-/// you want to tweak the size of the inner buffer. Check out the
-/// [`WriterBuilder`].
-pub struct Writer<W: Write> {
-    delimiter: u8,
-    quote: u8,
-    line_terminator: &'static [u8],
-    buf_writer: BufWriter<W>,
+/// Quoting & escaping logic used to write records to any [`Write`] target,
+/// shared by the sync & async writers.
+pub(crate) struct RecordEncoder {
+    delimiter: Delimiter,
+    terminator: Terminator,
+    quoting: Quoting,
+    line_terminator: Vec<u8>,
     flexible: bool,
     field_count: Option<usize>,
     must_quote: [bool; 256],
+    // NOTE: only used to write records when quoting is disabled, see
+    // `write_record_without_quoting`
+    scratch: Vec<u8>,
 }
 
-impl<W: Write> Writer<W> {
-    /// Create a new writer with default configuration using the provided writer
-    /// implementing [`std::io::Write`].
-    ///
-    /// Avoid providing a buffered writer because buffering will be handled for
-    /// you by the [`Writer`].
-    pub fn from_writer(writer: W) -> Self {
-        WriterBuilder::new().from_writer(writer)
-    }
-
-    /// Flush the underlying [`BufWriter`].
-    #[inline(always)]
-    pub fn flush(&mut self) -> io::Result<()> {
-        self.buf_writer.flush()
-    }
-
+impl RecordEncoder {
     #[inline]
     fn check_field_count(&mut self, written: usize) -> error::Result<()> {
         if self.flexible {
@@ -159,18 +265,13 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
-    /// Write the given "record" while foregoing any quoting/escaping.
-    ///
-    /// This method accepts any item implementing [`IntoIterator`] and yielding
-    /// references to byte slices.
-    ///
-    /// **BEWARE**: if written data needed escaping, invalid CSV will be
-    /// written!
-    ///
-    /// Only use this method when you can guarantee you are doing the right
-    /// thing and want the extra performance.
-    pub fn write_record_no_quoting<I, T>(&mut self, record: I) -> error::Result<()>
+    pub(crate) fn write_record_no_quoting<O, I, T>(
+        &mut self,
+        out: &mut O,
+        record: I,
+    ) -> error::Result<()>
     where
+        O: Write,
         I: IntoIterator<Item = T>,
         T: AsRef<[u8]>,
     {
@@ -182,7 +283,7 @@ impl<W: Write> Writer<W> {
             if first {
                 first = false;
             } else {
-                self.buf_writer.write_all(&[self.delimiter])?;
+                out.write_all(self.delimiter.as_bytes())?;
             }
 
             let cell = cell.as_ref();
@@ -191,34 +292,22 @@ impl<W: Write> Writer<W> {
                 empty = true;
             }
 
-            self.buf_writer.write_all(cell)?;
+            out.write_all(cell)?;
 
             written += 1;
         }
 
         if written == 1 && empty {
-            self.buf_writer.write_all(&[self.quote, self.quote])?;
+            out.write_all(&[self.quoting.quote, self.quoting.quote])?;
         }
 
         self.check_field_count(written)?;
 
-        self.buf_writer.write_all(self.line_terminator)?;
+        out.write_all(&self.line_terminator)?;
 
         Ok(())
     }
 
-    /// Write the given [`ByteRecord`] while foregoing any quoting/escaping.
-    ///
-    /// **BEWARE**: if written data needed escaping, invalid CSV will be
-    /// written!
-    ///
-    /// Only use this method when you can guarantee you are doing the right
-    /// thing and want the extra performance.
-    #[inline(always)]
-    pub fn write_byte_record_no_quoting(&mut self, record: &ByteRecord) -> error::Result<()> {
-        self.write_record_no_quoting(record.iter())
-    }
-
     #[inline]
     fn should_quote(&self, mut cell: &[u8]) -> bool {
         // This strategy comes directly from `rust-csv`
@@ -237,55 +326,140 @@ impl<W: Write> Writer<W> {
         yes || cell.iter().any(|&b| self.must_quote[b as usize])
     }
 
-    fn write_quoted_cell(&mut self, cell: &[u8]) -> error::Result<()> {
-        self.buf_writer.write_all(&[self.quote])?;
+    fn write_escaped_cell<O: Write>(
+        &self,
+        out: &mut O,
+        cell: &[u8],
+        escape: u8,
+    ) -> error::Result<()> {
+        let quote = self.quoting.quote;
+
+        out.write_all(&[quote])?;
+
+        let mut i: usize = 0;
+
+        while let Some(offset) = memchr2(quote, escape, &cell[i..]) {
+            let j = i + offset;
+            let byte = cell[j];
+
+            out.write_all(&cell[i..j])?;
+
+            if byte == quote && self.quoting.double_quote {
+                out.write_all(&[quote, quote])?;
+            } else {
+                out.write_all(&[escape, byte])?;
+            }
+
+            i = j + 1;
+        }
+
+        out.write_all(&cell[i..])?;
+        out.write_all(&[quote])?;
+
+        Ok(())
+    }
+
+    fn write_quoted_cell<O: Write>(&self, out: &mut O, cell: &[u8]) -> error::Result<()> {
+        if let Some(escape) = self.quoting.escape {
+            return self.write_escaped_cell(out, cell, escape);
+        }
+
+        out.write_all(&[self.quoting.quote])?;
 
         let mut i: usize = 0;
 
         if cell.len() < 8 {
             while i < cell.len() {
-                match cell[i..].iter().copied().position(|b| b == self.quote) {
+                match cell[i..]
+                    .iter()
+                    .copied()
+                    .position(|b| b == self.quoting.quote)
+                {
                     None => {
-                        self.buf_writer.write_all(&cell[i..])?;
+                        out.write_all(&cell[i..])?;
                         break;
                     }
                     Some(offset) => {
-                        self.buf_writer.write_all(&cell[i..i + offset + 1])?;
-                        self.buf_writer.write_all(&[self.quote])?;
+                        out.write_all(&cell[i..i + offset + 1])?;
+                        out.write_all(&[self.quoting.quote])?;
                         i += offset + 1;
                     }
                 }
             }
         } else {
             while i < cell.len() {
-                match memchr(self.quote, &cell[i..]) {
+                match memchr(self.quoting.quote, &cell[i..]) {
                     None => {
-                        self.buf_writer.write_all(&cell[i..])?;
+                        out.write_all(&cell[i..])?;
                         break;
                     }
                     Some(offset) => {
-                        self.buf_writer.write_all(&cell[i..i + offset + 1])?;
-                        self.buf_writer.write_all(&[self.quote])?;
+                        out.write_all(&cell[i..i + offset + 1])?;
+                        out.write_all(&[self.quoting.quote])?;
                         i += offset + 1;
                     }
                 };
             }
         }
 
-        self.buf_writer.write_all(&[self.quote])?;
+        out.write_all(&[self.quoting.quote])?;
 
         Ok(())
     }
 
-    /// Write the given "record".
-    ///
-    /// This method accepts any item implementing [`IntoIterator`] and yielding
-    /// references to byte slices.
-    pub fn write_record<I, T>(&mut self, record: I) -> error::Result<()>
+    // NOTE: without quoting, any cell may turn out to be unwritable, so the
+    // record is first validated into a scratch buffer to avoid writing a
+    // partial line.
+    fn write_record_without_quoting<O, I, T>(&mut self, out: &mut O, record: I) -> error::Result<()>
+    where
+        O: Write,
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut written: usize = 0;
+
+        self.scratch.clear();
+
+        for cell in record.into_iter() {
+            let cell = cell.as_ref();
+
+            if self.should_quote(cell) {
+                return Err(Error::new(ErrorKind::UnquotableCell));
+            }
+
+            if written > 0 {
+                self.scratch.extend_from_slice(self.delimiter.as_bytes());
+            }
+
+            self.scratch.extend_from_slice(cell);
+
+            written += 1;
+        }
+
+        // NOTE: a record having a single empty cell could only be written as
+        // an empty line, which would be skipped when read.
+        if written == 1 && self.scratch.is_empty() {
+            return Err(Error::new(ErrorKind::UnquotableCell));
+        }
+
+        self.check_field_count(written)?;
+
+        out.write_all(&self.scratch)?;
+        out.write_all(&self.line_terminator)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn write_record<O, I, T>(&mut self, out: &mut O, record: I) -> error::Result<()>
     where
+        O: Write,
         I: IntoIterator<Item = T>,
         T: AsRef<[u8]>,
     {
+        if !self.quoting.enabled {
+            return self.write_record_without_quoting(out, record);
+        }
+
         let mut first = true;
         let mut written: usize = 0;
         let mut empty = false;
@@ -294,7 +468,7 @@ impl<W: Write> Writer<W> {
             if first {
                 first = false;
             } else {
-                self.buf_writer.write_all(&[self.delimiter])?;
+                out.write_all(self.delimiter.as_bytes())?;
             }
 
             let cell = cell.as_ref();
@@ -304,70 +478,224 @@ impl<W: Write> Writer<W> {
             }
 
             if self.should_quote(cell) {
-                self.write_quoted_cell(cell)?;
+                self.write_quoted_cell(out, cell)?;
             } else {
-                self.buf_writer.write_all(cell)?;
+                out.write_all(cell)?;
             }
 
             written += 1;
         }
 
         if written == 1 && empty {
-            self.buf_writer.write_all(&[self.quote, self.quote])?;
+            out.write_all(&[self.quoting.quote, self.quoting.quote])?;
         }
 
         self.check_field_count(written)?;
 
-        self.buf_writer.write_all(self.line_terminator)?;
+        out.write_all(&self.line_terminator)?;
 
         Ok(())
     }
 
-    /// Write the given [`ByteRecord`].
+    // NOTE: a zero-copy record can only be written as is when it was read
+    // using a compatible dialect, since its cells could otherwise contain
+    // unquoted bytes that would break the written record.
     #[inline(always)]
-    pub fn write_byte_record(&mut self, record: &ByteRecord) -> error::Result<()> {
-        self.write_record(record.iter())
+    fn can_write_raw(&self, record: &ZeroCopyByteRecord) -> bool {
+        record.delimiter == self.delimiter
+            && record.terminator == self.terminator
+            && record.quoting == self.quoting
+            && !record.trim
     }
 
-    /// Write the given [`ZeroCopyByteRecord`] using a fast path if reader & writer
-    /// have matching delimiter & quote.
-    ///
-    /// A [`ZeroCopyByteRecord`] keeps track of its quote, but not the delimiter so
-    /// this one must be provided to the method.
     #[inline]
-    pub fn write_zero_copy_byte_record(
+    pub(crate) fn write_zero_copy_byte_record<O: Write>(
         &mut self,
-        delimiter: u8,
+        out: &mut O,
         record: &ZeroCopyByteRecord,
     ) -> error::Result<()> {
-        if self.delimiter == delimiter && record.quote == self.quote {
-            self.buf_writer.write_all(record.as_slice())?;
-            self.buf_writer.write_all(self.line_terminator)?;
+        if self.can_write_raw(record) {
+            out.write_all(record.as_slice())?;
+            out.write_all(&self.line_terminator)?;
         } else {
-            self.write_record(record.unescaped_iter())?;
+            self.write_record(out, record.unescaped_iter())?;
         }
 
         Ok(())
     }
 
-    /// Same as [`Self::write_zero_copy_byte_record`], but only write the
-    /// given selection of cell indices.
     #[inline]
-    pub fn write_zero_copy_byte_record_indices(
+    pub(crate) fn write_zero_copy_byte_record_indices<O: Write>(
         &mut self,
-        delimiter: u8,
+        out: &mut O,
         record: &ZeroCopyByteRecord,
         indices: &[usize],
     ) -> error::Result<()> {
-        if self.delimiter == delimiter && record.quote == self.quote {
-            self.write_record_no_quoting(indices.iter().copied().map(|i| &record[i]))?;
+        if self.can_write_raw(record) {
+            self.write_record_no_quoting(out, indices.iter().copied().map(|i| &record[i]))?;
         } else {
-            self.write_record(indices.iter().copied().map(|i| record.unescape(i).unwrap()))?;
+            self.write_record(
+                out,
+                indices.iter().copied().map(|i| record.unescape(i).unwrap()),
+            )?;
         }
 
         Ok(())
     }
 
+    #[inline(always)]
+    pub(crate) fn write_splitted_record<O: Write>(
+        &self,
+        out: &mut O,
+        record: &[u8],
+    ) -> error::Result<()> {
+        out.write_all(record)?;
+        out.write_all(&self.line_terminator)?;
+
+        Ok(())
+    }
+}
+
+/// An already configured CSV writer.
+///
+/// # Configuration
+///
+/// To configure a [`Writer`], if you need a custom delimiter for instance of if
+/// you want to tweak the size of the inner buffer. Check out the
+/// [`WriterBuilder`].
+pub struct Writer<W: Write> {
+    encoder: RecordEncoder,
+    buf_writer: BufWriter<W>,
+    #[cfg(feature = "serde")]
+    record: ByteRecord,
+    #[cfg(feature = "serde")]
+    must_write_headers: bool,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a new writer with default configuration using the provided writer
+    /// implementing [`std::io::Write`].
+    ///
+    /// Avoid providing a buffered writer because buffering will be handled for
+    /// you by the [`Writer`].
+    pub fn from_writer(writer: W) -> Self {
+        WriterBuilder::new().from_writer(writer)
+    }
+
+    /// Flush the underlying [`BufWriter`].
+    #[inline(always)]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.buf_writer.flush()
+    }
+
+    /// Write the given "record" while foregoing any quoting/escaping.
+    ///
+    /// This method accepts any item implementing [`IntoIterator`] and yielding
+    /// references to byte slices.
+    ///
+    /// **BEWARE**: if written data needed escaping, invalid CSV will be
+    /// written!
+    ///
+    /// Only use this method when you can guarantee you are doing the right
+    /// thing and want the extra performance.
+    pub fn write_record_no_quoting<I, T>(&mut self, record: I) -> error::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.encoder
+            .write_record_no_quoting(&mut self.buf_writer, record)
+    }
+
+    /// Write the given [`ByteRecord`] while foregoing any quoting/escaping.
+    ///
+    /// **BEWARE**: if written data needed escaping, invalid CSV will be
+    /// written!
+    ///
+    /// Only use this method when you can guarantee you are doing the right
+    /// thing and want the extra performance.
+    #[inline(always)]
+    pub fn write_byte_record_no_quoting(&mut self, record: &ByteRecord) -> error::Result<()> {
+        self.write_record_no_quoting(record.iter())
+    }
+
+    /// Write the given "record".
+    ///
+    /// This method accepts any item implementing [`IntoIterator`] and yielding
+    /// references to byte slices.
+    pub fn write_record<I, T>(&mut self, record: I) -> error::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.encoder.write_record(&mut self.buf_writer, record)
+    }
+
+    /// Write the given [`ByteRecord`].
+    #[inline(always)]
+    pub fn write_byte_record(&mut self, record: &ByteRecord) -> error::Result<()> {
+        self.write_record(record.iter())
+    }
+
+    /// Serialize the given value, which must implement [`serde::Serialize`],
+    /// as a CSV record.
+    ///
+    /// Structs, maps, tuples and sequences are written as one field per
+    /// element, while scalars are written as a record having a single field.
+    /// Fields containing nested structures cannot be represented and will
+    /// return an [`ErrorKind::Serialize`] error. `None` is written as an empty
+    /// field.
+    ///
+    /// Unless the writer was configured otherwise using
+    /// [`WriterBuilder::has_headers`], a header row made of struct field names
+    /// or map keys will be written before the first record.
+    #[cfg(feature = "serde")]
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> error::Result<()> {
+        self.record.clear();
+
+        if self.must_write_headers {
+            let mut headers = ByteRecord::new();
+
+            serialize_record(value, &mut self.record, Some(&mut headers))?;
+
+            self.must_write_headers = false;
+
+            if !headers.is_empty() {
+                self.encoder
+                    .write_record(&mut self.buf_writer, headers.iter())?;
+            }
+        } else {
+            serialize_record(value, &mut self.record, None)?;
+        }
+
+        self.encoder
+            .write_record(&mut self.buf_writer, self.record.iter())
+    }
+
+    /// Write the given [`ZeroCopyByteRecord`] using a fast path if reader & writer
+    /// have matching delimiter, terminator & quoting, and the reader does not
+    /// trim fields.
+    #[inline]
+    pub fn write_zero_copy_byte_record(
+        &mut self,
+        record: &ZeroCopyByteRecord,
+    ) -> error::Result<()> {
+        self.encoder
+            .write_zero_copy_byte_record(&mut self.buf_writer, record)
+    }
+
+    /// Same as [`Self::write_zero_copy_byte_record`], but only write the
+    /// given selection of cell indices.
+    #[inline]
+    pub fn write_zero_copy_byte_record_indices(
+        &mut self,
+        record: &ZeroCopyByteRecord,
+        indices: &[usize],
+    ) -> error::Result<()> {
+        self.encoder
+            .write_zero_copy_byte_record_indices(&mut self.buf_writer, record, indices)
+    }
+
     /// Write the given byte slice, as-is, without quoting/escaping, with an
     /// added newline.
     ///
@@ -378,10 +706,8 @@ impl<W: Write> Writer<W> {
     /// [`Splitter.split_record`](crate::Splitter::split_record).
     #[inline(always)]
     pub fn write_splitted_record(&mut self, record: &[u8]) -> error::Result<()> {
-        self.buf_writer.write_all(record)?;
-        self.buf_writer.write_all(self.line_terminator)?;
-
-        Ok(())
+        self.encoder
+            .write_splitted_record(&mut self.buf_writer, record)
     }
 
     /// Attempt to unwrap the underlying [`BufWriter`] by flusing it and
@@ -432,17 +758,285 @@ mod tests {
     }
 
     #[test]
-    fn should_quote() {
-        let writer = Writer::from_writer(Cursor::new(Vec::<u8>::new()));
+    fn test_write_escape() {
+        fn write(record: &ByteRecord, double_quote: bool) -> String {
+            let output = Cursor::new(Vec::<u8>::new());
+            let mut writer = WriterBuilder::new()
+                .escape(Some(b'\\'))
+                .double_quote(double_quote)
+                .from_writer(output);
+            writer.write_byte_record(record).unwrap();
+            String::from_utf8_lossy(&writer.into_inner().unwrap().into_inner()).into_owned()
+        }
+
+        assert_eq!(write(&brec!["te\\st", "ok"], false), "te\\st,ok\n");
+        assert_eq!(
+            write(&brec!["\"hello\"", "back\\slash,"], false),
+            "\"\\\"hello\\\"\",\"back\\\\slash,\"\n"
+        );
+        assert_eq!(
+            write(&brec!["\"hello\\\""], true),
+            "\"\"\"hello\\\\\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_terminator() {
+        fn write(terminator: Terminator) -> Vec<u8> {
+            let mut writer = WriterBuilder::new()
+                .terminator(terminator)
+                .from_writer(Vec::new());
+            writer.write_byte_record(&brec!["name", "a\x1eb"]).unwrap();
+            writer.write_byte_record(&brec!["john", "c\rd"]).unwrap();
+            writer.into_inner().unwrap()
+        }
+
+        assert_eq!(write(Terminator::CRLF), b"name,a\x1eb\r\njohn,\"c\rd\"\r\n");
+        assert_eq!(
+            write(Terminator::Any(b'\x1e')),
+            b"name,\"a\x1eb\"\x1ejohn,\"c\rd\"\x1e"
+        );
+    }
+
+    #[test]
+    fn test_write_multi_byte_delimiter() {
+        let mut writer = WriterBuilder::new()
+            .delimiter_bytes(b"||")
+            .from_writer(Vec::new());
+        writer.write_byte_record(&brec!["name", "surname"]).unwrap();
+        writer.write_byte_record(&brec!["a|b", "c"]).unwrap();
+
+        assert_eq!(writer.into_inner().unwrap(), b"name||surname\n\"a|b\"||c\n");
+    }
+
+    #[test]
+    fn test_write_zero_copy_byte_record() -> error::Result<()> {
+        let data = b" \"john\" , \"la\"\"ndis\"  ";
+        let seps = [8];
+
+        for trim in [false, true] {
+            let record = ZeroCopyByteRecord::new(
+                data,
+                &seps,
+                Delimiter::from(b','),
+                Terminator::Any(b'\n'),
+                Quoting::default(),
+                trim,
+            );
+
+            let mut writer = Writer::from_writer(Vec::new());
+            writer.write_zero_copy_byte_record(&record)?;
+            writer.write_zero_copy_byte_record_indices(&record, &[1, 0])?;
+
+            let expected: &[u8] = if trim {
+                b"john,\"la\"\"ndis\"\n\"la\"\"ndis\",john\n"
+            } else {
+                b" \"john\" , \"la\"\"ndis\"  \n \"la\"\"ndis\"  , \"john\" \n"
+            };
+
+            assert_eq!(writer.into_inner().unwrap(), expected);
+        }
+
+        // Records read using another terminator cannot be written as is
+        let record = ZeroCopyByteRecord::new(
+            b"a\nb,c",
+            &[3],
+            Delimiter::from(b','),
+            Terminator::Any(0x1e),
+            Quoting::default(),
+            false,
+        );
+
+        let mut writer = Writer::from_writer(Vec::new());
+        writer.write_zero_copy_byte_record(&record)?;
+        writer.write_zero_copy_byte_record_indices(&record, &[1, 0])?;
+
+        assert_eq!(writer.into_inner().unwrap(), b"\"a\nb\",c\nc,\"a\nb\"\n");
+
+        // Even when both terminators end with LF
+        let record = ZeroCopyByteRecord::new(
+            b"a\rb,c",
+            &[3],
+            Delimiter::from(b','),
+            Terminator::Any(b'\n'),
+            Quoting::default(),
+            false,
+        );
+
+        let mut writer = WriterBuilder::new()
+            .terminator(Terminator::CRLF)
+            .from_writer(Vec::new());
+        writer.write_zero_copy_byte_record(&record)?;
+        writer.write_zero_copy_byte_record_indices(&record, &[1, 0])?;
+
+        assert_eq!(
+            writer.into_inner().unwrap(),
+            b"\"a\rb\",c\r\nc,\"a\rb\"\r\n"
+        );
+
+        let record = ZeroCopyByteRecord::new(
+            b" a ,\"b\"",
+            &[3],
+            Delimiter::from(b','),
+            Terminator::CRLF,
+            Quoting::default(),
+            false,
+        );
+
+        let mut writer = Writer::from_writer(Vec::new());
+        writer.write_zero_copy_byte_record(&record)?;
+
+        assert_eq!(writer.into_inner().unwrap(), b" a ,b\n");
 
-        assert_eq!(writer.should_quote(b"test"), false);
-        assert_eq!(writer.should_quote(b"test,"), true);
-        assert_eq!(writer.should_quote(b"te\"st"), true);
-        assert_eq!(writer.should_quote(b"te\nst"), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_without_quoting() {
+        let mut writer = WriterBuilder::new()
+            .delimiter(b'\t')
+            .quoting(false)
+            .from_writer(Vec::new());
+
+        writer.write_byte_record(&brec!["\"john\"", "a,b"]).unwrap();
+
+        // Nothing from the offending record should be written
+        for record in [brec!["a", "a\tb"], brec!["a", "a\nb"], brec![""]] {
+            assert!(matches!(
+                writer.write_byte_record(&record).unwrap_err().kind(),
+                ErrorKind::UnquotableCell
+            ));
+        }
+
+        writer.write_byte_record(&brec!["a\rb", ""]).unwrap();
+
+        assert_eq!(writer.into_inner().unwrap(), b"\"john\"\ta,b\na\rb\t\n");
+
+        // Only bytes of the configured terminator are unwritable
+        let mut writer = WriterBuilder::new()
+            .quoting(false)
+            .terminator(Terminator::Any(b';'))
+            .from_writer(Vec::new());
+
+        writer.write_byte_record(&brec!["a\nb", "c\rd"]).unwrap();
+
+        assert!(matches!(
+            writer
+                .write_byte_record(&brec!["a;b", "c"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::UnquotableCell
+        ));
+
+        assert_eq!(writer.into_inner().unwrap(), b"a\nb,c\rd;");
+
+        // Both bytes of CRLF are unwritable, since a trailing CR would be
+        // trimmed when read
+        let mut writer = WriterBuilder::new()
+            .quoting(false)
+            .terminator(Terminator::CRLF)
+            .from_writer(Vec::new());
+
+        for record in [brec!["a", "b\r"], brec!["a\rb", "c"], brec!["a\nb", "c"]] {
+            assert!(matches!(
+                writer.write_byte_record(&record).unwrap_err().kind(),
+                ErrorKind::UnquotableCell
+            ));
+        }
+
+        writer.write_byte_record(&brec!["a", "b"]).unwrap();
+
+        assert_eq!(writer.into_inner().unwrap(), b"a,b\r\n");
+
+        let mut writer = WriterBuilder::new()
+            .quoting(false)
+            .crlf_newlines(true)
+            .from_writer(Vec::new());
+
+        for cell in ["a\rb", "a\nb"] {
+            assert!(matches!(
+                writer.write_byte_record(&brec![cell]).unwrap_err().kind(),
+                ErrorKind::UnquotableCell
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() -> error::Result<()> {
+        use std::collections::BTreeMap;
+
+        #[derive(serde::Serialize)]
+        struct Row<'a> {
+            name: &'a str,
+            age: u8,
+            score: Option<f64>,
+            alive: bool,
+        }
+
+        let mut writer = Writer::from_writer(Vec::new());
+
+        writer.serialize(&Row {
+            name: "john, the \"king\"",
+            age: 45,
+            score: Some(4.5),
+            alive: true,
+        })?;
+        writer.serialize(&Row {
+            name: "lucy",
+            age: 67,
+            score: None,
+            alive: false,
+        })?;
+
+        assert_eq!(
+            std::str::from_utf8(&writer.into_inner().unwrap()).unwrap(),
+            "name,age,score,alive\n\"john, the \"\"king\"\"\",45,4.5,true\nlucy,67,,false\n"
+        );
+
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_writer(Vec::new());
+
+        writer.serialize(&BTreeMap::from([("a", 1), ("b", 2)]))?;
+        writer.serialize(&("c", 'd', -3i64))?;
+        writer.serialize(&[0.5f32, 1.0])?;
+
+        assert_eq!(writer.into_inner().unwrap(), b"1,2\nc,d,-3\n0.5,1\n");
+
+        let mut writer = Writer::from_writer(Vec::new());
+
+        writer.serialize(&BTreeMap::from([("a", 1), ("b", 2)]))?;
+        writer.serialize(&("name", 1))?;
+
+        assert_eq!(writer.into_inner().unwrap(), b"a,b\n1,2\nname,1\n");
+
+        // Nested structures
+        let mut writer = Writer::from_writer(Vec::new());
+
+        let err = writer.serialize(&("name", vec![1, 2])).unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::Serialize(_)));
         assert_eq!(
-            writer.should_quote(b"testtesttesttesttesttesttesttest\n"),
-            true
+            err.to_string(),
+            "CSV serialize error: cannot serialize a nested sequence as a CSV field"
         );
-        assert_eq!(writer.should_quote(b"te\rst"), true);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_quote() {
+        let writer = Writer::from_writer(Cursor::new(Vec::<u8>::new()));
+
+        assert!(!writer.encoder.should_quote(b"test"));
+        assert!(writer.encoder.should_quote(b"test,"));
+        assert!(writer.encoder.should_quote(b"te\"st"));
+        assert!(writer.encoder.should_quote(b"te\nst"));
+        assert!(writer
+            .encoder
+            .should_quote(b"testtesttesttesttesttesttesttest\n"));
+        assert!(writer.encoder.should_quote(b"te\rst"));
     }
 }
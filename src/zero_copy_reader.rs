@@ -1,31 +1,42 @@
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
 
 use crate::buffer::ScratchBuffer;
-use crate::core::{CoreReader, ReadResult};
-use crate::error::{self, Error, ErrorKind};
+use crate::checkpoint::Checkpoint;
+use crate::core::CoreReader;
+use crate::dialect::{Delimiter, Dialect, Terminator, Trim};
+use crate::error;
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::preamble::SkipLines;
 use crate::reader::ReaderBuilder;
 use crate::records::{ByteRecord, ZeroCopyByteRecord};
 use crate::splitter::SplitterBuilder;
-use crate::utils::trim_bom;
+use crate::tracker::{Step, Tracker};
+use crate::utils::{count_lines, trim_bom};
 
 /// Builds a [`ZeroCopyReader`] with given configuration.
 #[derive(Clone)]
 pub struct ZeroCopyReaderBuilder {
-    delimiter: u8,
-    quote: u8,
-    buffer_capacity: usize,
-    flexible: bool,
-    has_headers: bool,
+    pub(crate) dialect: Dialect,
+    pub(crate) limits: Limits,
+    pub(crate) buffer_capacity: usize,
+    pub(crate) flexible: bool,
+    pub(crate) has_headers: bool,
+    pub(crate) trim: Trim,
+    skip_footer: usize,
 }
 
 impl Default for ZeroCopyReaderBuilder {
     fn default() -> Self {
         Self {
-            delimiter: b',',
-            quote: b'"',
+            dialect: Dialect::default(),
+            limits: Limits::default(),
             buffer_capacity: 8192,
             flexible: false,
             has_headers: true,
+            trim: Trim::None,
+            skip_footer: 0,
         }
     }
 }
@@ -45,11 +56,27 @@ impl ZeroCopyReaderBuilder {
 
     /// Set the delimiter to be used by the created [`ZeroCopyReader`].
     ///
-    /// This delimiter must be a single byte.
+    /// This delimiter must be a single byte. Use [`Self::delimiter_bytes`]
+    /// for delimiters spanning multiple bytes.
     ///
     /// Will default to a comma.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
-        self.delimiter = delimiter;
+        self.dialect.delimiter = Delimiter::from(delimiter);
+        self
+    }
+
+    /// Set a delimiter spanning multiple bytes, e.g. `b"||"` or `"¦".as_bytes()`,
+    /// to be used by the created [`ZeroCopyReader`].
+    ///
+    /// Only the first byte is used to search the data, the rest being
+    /// verified on match, so this remains fast as long as the first byte
+    /// is rare enough.
+    ///
+    /// # Panics
+    ///
+    /// If the delimiter is empty or longer than 4 bytes.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> &mut Self {
+        self.dialect.delimiter = Delimiter::new(delimiter);
         self
     }
 
@@ -59,7 +86,124 @@ impl ZeroCopyReaderBuilder {
     ///
     /// Will default to a double quote.
     pub fn quote(&mut self, quote: u8) -> &mut Self {
-        self.quote = quote;
+        self.dialect.quote = quote;
+        self
+    }
+
+    /// Indicate whether the created [`ZeroCopyReader`] should handle quoting at all.
+    ///
+    /// When disabled, quote chars are considered as any other byte, which is
+    /// useful to read TSV-like data where cells may legitimately start with
+    /// a quote, and slightly faster since quotes are not searched anymore.
+    ///
+    /// Will default to `true`.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.dialect.quoting = yes;
+        self
+    }
+
+    /// Set the escape char to be used by the created [`ZeroCopyReader`].
+    ///
+    /// When given, this byte will escape the byte following it in quoted
+    /// fields, e.g. `\"` when using a backslash. This is typically used with
+    /// [`Self::double_quote`] set to `false`.
+    ///
+    /// Will default to `None`, i.e. no escape char.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.dialect.escape = escape;
+        self
+    }
+
+    /// Indicate whether doubled quotes in quoted fields must be understood
+    /// as escaped quotes by the created [`ZeroCopyReader`].
+    ///
+    /// Will default to `true`.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.dialect.double_quote = yes;
+        self
+    }
+
+    /// Set the record terminator to be used by the created [`ZeroCopyReader`].
+    ///
+    /// Will default to [`Terminator::CRLF`], i.e. records will be terminated
+    /// by either LF or CRLF.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Self {
+        self.dialect.terminator = terminator;
+        self
+    }
+
+    /// Set the comment char to be used by the created [`ZeroCopyReader`].
+    ///
+    /// Lines starting with this byte will be skipped entirely, no matter
+    /// what they contain (quotes, delimiters etc.). Note that comment lines are
+    /// only recognized at the beginning of a record, not in the middle of one.
+    ///
+    /// Will default to `None`, i.e. no comment lines.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.dialect.comment = comment;
+        self
+    }
+
+    /// Indicate whether the created [`ZeroCopyReader`] should be strict regarding
+    /// quoting, i.e. whether it should return an error when finding a quote
+    /// in the middle of an unquoted field, or unexpected data after a closing
+    /// quote, instead of trying its best to make sense of it.
+    ///
+    /// A strict [`ZeroCopyReader`] will also return an error when reaching the end of
+    /// the stream in the middle of a quoted field, which typically happens
+    /// when reading truncated data. A non-strict one will yield the truncated
+    /// record as is, but [`ZeroCopyReader::ended_in_quote`] can be checked afterwards.
+    ///
+    /// Will default to `false`.
+    pub fn strict(&mut self, yes: bool) -> &mut Self {
+        self.dialect.strict = yes;
+        self
+    }
+
+    /// Set the maximum number of bytes a single record may span in the stream
+    /// read by the created [`ZeroCopyReader`]. Reading a larger record will return an
+    /// [`ErrorKind::RecordTooLarge`](crate::ErrorKind::RecordTooLarge) error
+    /// instead of buffering it indefinitely.
+    ///
+    /// Will default to no limit.
+    pub fn max_record_size(&mut self, size: usize) -> &mut Self {
+        self.limits.max_record_size = Some(size);
+        self
+    }
+
+    /// Set the maximum number of fields a single record may have when read
+    /// by the created [`ZeroCopyReader`]. Reading a record with more fields will return an
+    /// [`ErrorKind::TooManyFields`](crate::ErrorKind::TooManyFields) error.
+    ///
+    /// Will default to no limit.
+    pub fn max_fields(&mut self, fields: usize) -> &mut Self {
+        self.limits.max_fields = Some(fields);
+        self
+    }
+
+    /// Indicate whether the created [`ZeroCopyReader`] should skip empty lines.
+    ///
+    /// When set to `false`, empty lines will be yielded as records having a
+    /// single empty field. Note that unless the reader is also flexible, this
+    /// will fail when reading CSV data having more than a single column.
+    ///
+    /// Will default to `true`.
+    pub fn skip_empty_lines(&mut self, yes: bool) -> &mut Self {
+        self.dialect.skip_empty_lines = yes;
+        self
+    }
+
+    /// Indicate whether the created [`ZeroCopyReader`] should only consider quotes
+    /// found at the very beginning of a field as opening a quoted field, like
+    /// the `csv` crate does, instead of entering the quoted state on any
+    /// quote. This way, dirty data such as `joh"n` won't misalign records.
+    ///
+    /// This mode is slightly slower and makes [`Self::strict`] only report
+    /// unexpected data after closing quotes.
+    ///
+    /// Will default to `false`.
+    pub fn lenient_quotes(&mut self, yes: bool) -> &mut Self {
+        self.dialect.lenient_quotes = yes;
         self
     }
 
@@ -79,6 +223,34 @@ impl ZeroCopyReaderBuilder {
         self
     }
 
+    /// Set a number of raw lines to skip before reading the header row of the
+    /// created [`ZeroCopyReader`], e.g. to discard some metadata preamble. Those lines
+    /// are split on the record terminator without any regard for quoting, and
+    /// can be retrieved afterwards using [`ZeroCopyReader::preamble`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_lines(&mut self, count: usize) -> &mut Self {
+        self.dialect.skip_lines = if count == 0 {
+            SkipLines::None
+        } else {
+            SkipLines::Count(count)
+        };
+        self
+    }
+
+    /// Skip raw lines before reading the header row of the created
+    /// [`ZeroCopyReader`], as long as they satisfy the given predicate, which receives
+    /// lines without their terminator.
+    ///
+    /// See [`Self::skip_lines`] for more detail.
+    pub fn skip_lines_while<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.dialect.skip_lines = SkipLines::While(Arc::new(predicate));
+        self
+    }
+
     /// Indicate whether first record must be understood as a header.
     ///
     /// Will default to `true`.
@@ -87,15 +259,44 @@ impl ZeroCopyReaderBuilder {
         self
     }
 
+    /// Set whether the created [`ZeroCopyReader`] should trim ASCII whitespace around
+    /// headers and/or fields. Quoted fields are only trimmed outside of
+    /// their quotes.
+    ///
+    /// Note that fields are trimmed lazily, when accessed through the
+    /// yielded [`ZeroCopyByteRecord`].
+    ///
+    /// Will default to [`Trim::None`].
+    pub fn trim(&mut self, trim: Trim) -> &mut Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Set a number of records to set aside at the end of the stream, e.g. to
+    /// discard some "TOTAL" footer row. Those records will not be checked
+    /// against the header row length and can be retrieved using
+    /// [`ZeroCopyReader::footer`].
+    ///
+    /// Since the stream must be read in reverse to find them, this option is
+    /// only taken into account by [`Self::from_seekable_reader`] and
+    /// [`Self::resume_from_reader`].
+    ///
+    /// Will default to `0`.
+    pub fn skip_footer(&mut self, count: usize) -> &mut Self {
+        self.skip_footer = count;
+        self
+    }
+
     /// Create a matching [`SplitterBuilder`] from this builder.
     pub fn to_splitter_builder(&self) -> SplitterBuilder {
         let mut splitter_builder = SplitterBuilder::new();
 
         splitter_builder
             .buffer_capacity(self.buffer_capacity)
-            .has_headers(self.has_headers)
-            .quote(self.quote)
-            .delimiter(self.delimiter);
+            .has_headers(self.has_headers);
+
+        splitter_builder.dialect = self.dialect.clone();
+        splitter_builder.limits = self.limits;
 
         splitter_builder
     }
@@ -106,9 +307,12 @@ impl ZeroCopyReaderBuilder {
 
         reader_builder
             .buffer_capacity(self.buffer_capacity)
-            .has_headers(self.has_headers)
-            .quote(self.quote)
-            .delimiter(self.delimiter);
+            .has_headers(self.has_headers);
+
+        reader_builder.dialect = self.dialect.clone();
+        reader_builder.limits = self.limits;
+        reader_builder.trim = self.trim;
+        reader_builder.skip_footer = self.skip_footer;
 
         reader_builder
     }
@@ -118,17 +322,93 @@ impl ZeroCopyReaderBuilder {
     pub fn from_reader<R: Read>(&self, reader: R) -> ZeroCopyReader<R> {
         ZeroCopyReader {
             buffer: ScratchBuffer::with_capacity(self.buffer_capacity, reader),
-            inner: CoreReader::new(self.delimiter, self.quote),
+            inner: CoreReader::new(&self.dialect),
+            tracker: Tracker::new(self.limits),
             byte_headers: ByteRecord::new(),
             raw_headers: (Vec::new(), Vec::new()),
+            headers_position: Position::new(),
             seps: Vec::new(),
             flexible: self.flexible,
             has_read: false,
             must_reemit_headers: !self.has_headers,
             has_headers: self.has_headers,
-            index: 0,
+            trim: self.trim,
+            skip_lines: self.dialect.skip_lines.clone(),
+            preamble: Vec::new(),
+            had_bom: false,
+            footer: Vec::new(),
+            data_end: None,
         }
     }
+
+    /// Create a new [`ZeroCopyReader`] resuming reading where the one a [`Checkpoint`]
+    /// was taken from stopped, using the provided reader implementing both
+    /// [`std::io::Read`] and [`std::io::Seek`].
+    ///
+    /// The given reader must stand where the checkpointed one started, e.g.
+    /// at the beginning of the same file. The dialect recorded by the
+    /// checkpoint will be used instead of the one of this builder.
+    ///
+    /// Footer records are found beforehand, as with
+    /// [`Self::from_seekable_reader`]. See [`Self::skip_footer`] for more detail.
+    pub fn resume_from_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+        checkpoint: &Checkpoint,
+    ) -> error::Result<ZeroCopyReader<R>> {
+        let mut csv_reader = self.resumed(checkpoint).from_seekable_reader(reader)?;
+
+        csv_reader.inner.trim = self.trim.fields();
+        csv_reader.byte_headers = checkpoint.byte_headers().clone();
+        csv_reader.raw_headers = checkpoint.raw_headers();
+        csv_reader.must_reemit_headers = false;
+        csv_reader.has_read = true;
+
+        csv_reader.seek(checkpoint.position())?;
+
+        Ok(csv_reader)
+    }
+
+    // NOTE: see `ReaderBuilder::resumed`
+    fn resumed(&self, checkpoint: &Checkpoint) -> Self {
+        let mut builder = self.clone();
+
+        builder.has_headers = checkpoint.has_headers();
+        builder.dialect = Dialect {
+            skip_lines: self.dialect.skip_lines.clone(),
+            ..checkpoint.dialect().clone()
+        };
+
+        builder
+    }
+
+    /// Create a new [`ZeroCopyReader`] using the provided reader implementing
+    /// both [`std::io::Read`] and [`std::io::Seek`], so that footer records
+    /// can be found beforehand by reading the end of the stream in reverse.
+    ///
+    /// See [`Self::skip_footer`] for more detail.
+    pub fn from_seekable_reader<R: Read + Seek>(
+        &self,
+        mut reader: R,
+    ) -> error::Result<ZeroCopyReader<R>> {
+        let initial_pos = reader.stream_position()?;
+
+        if self.skip_footer == 0 {
+            return Ok(self.from_reader(reader));
+        }
+
+        let reverse_reader = self.to_reader_builder().reverse_from_reader(&mut reader)?;
+        let footer = reverse_reader.footer().to_vec();
+        let data_end = reverse_reader.data_end() - initial_pos;
+
+        reader.seek(SeekFrom::Start(initial_pos))?;
+
+        let mut forward_reader = self.from_reader(reader);
+        forward_reader.footer = footer;
+        forward_reader.data_end = Some(data_end);
+
+        Ok(forward_reader)
+    }
 }
 
 /// An already configured zero-copy CSV reader.
@@ -141,14 +421,23 @@ impl ZeroCopyReaderBuilder {
 pub struct ZeroCopyReader<R> {
     buffer: ScratchBuffer<R>,
     inner: CoreReader,
+    tracker: Tracker,
     byte_headers: ByteRecord,
     raw_headers: (Vec<usize>, Vec<u8>),
+    headers_position: Position,
     seps: Vec<usize>,
     flexible: bool,
     has_read: bool,
     must_reemit_headers: bool,
     has_headers: bool,
-    index: u64,
+    trim: Trim,
+    skip_lines: SkipLines,
+    preamble: Vec<u8>,
+    had_bom: bool,
+    footer: Vec<ByteRecord>,
+    // NOTE: position where footer records start, relative to the beginning
+    // of the stream
+    data_end: Option<u64>,
 }
 
 impl<R: Read> ZeroCopyReader<R> {
@@ -156,25 +445,6 @@ impl<R: Read> ZeroCopyReader<R> {
         ZeroCopyReaderBuilder::new().from_reader(reader)
     }
 
-    #[inline]
-    fn check_field_count(&mut self, byte: u64, written: usize) -> error::Result<()> {
-        if self.flexible {
-            return Ok(());
-        }
-
-        let headers_len = self.raw_headers.0.len() + 1;
-
-        if self.has_read && written != headers_len {
-            return Err(Error::new(ErrorKind::UnequalLengths {
-                expected_len: headers_len,
-                len: written,
-                pos: Some((byte, self.index)),
-            }));
-        }
-
-        Ok(())
-    }
-
     #[inline]
     fn on_first_read(&mut self) -> error::Result<()> {
         if self.has_read {
@@ -185,21 +455,37 @@ impl<R: Read> ZeroCopyReader<R> {
         let input = self.buffer.fill_buf()?;
         let bom_len = trim_bom(input);
         self.buffer.consume(bom_len);
+        self.had_bom = bom_len > 0;
+
+        // Skipping preamble
+        self.preamble = self.skip_lines.skip(self.buffer.inner_mut(), &self.inner)?;
+        self.tracker.line += count_lines(&self.preamble, self.inner.newline());
 
         // Reading headers
         let mut headers_seps = Vec::new();
         let mut headers_slice = Vec::new();
         let mut byte_headers = ByteRecord::new();
+        let mut headers_position = Position::new();
+
+        self.inner.trim = if self.has_headers {
+            self.trim.headers()
+        } else {
+            self.trim.fields()
+        };
 
         if let Some(headers) = self.read_byte_record_impl()? {
             (headers_seps, headers_slice) = headers.to_parts();
+            headers_position = headers.position().copied().unwrap_or_default();
             byte_headers = headers.to_byte_record();
         } else {
             self.must_reemit_headers = false;
         }
 
+        self.inner.trim = self.trim.fields();
+
         self.raw_headers = (headers_seps, headers_slice);
         self.byte_headers = byte_headers;
+        self.headers_position = headers_position;
 
         self.has_read = true;
 
@@ -214,6 +500,33 @@ impl<R: Read> ZeroCopyReader<R> {
         Ok(&self.byte_headers)
     }
 
+    /// Attempt to return the raw preamble lines that were skipped before the
+    /// header row, terminators included.
+    #[inline]
+    pub fn preamble(&mut self) -> error::Result<&[u8]> {
+        self.on_first_read()?;
+
+        Ok(&self.preamble)
+    }
+
+    /// Attempt to return whether the input started with a UTF-8 BOM, which
+    /// was trimmed.
+    #[inline]
+    pub fn input_had_bom(&mut self) -> error::Result<bool> {
+        self.on_first_read()?;
+
+        Ok(self.had_bom)
+    }
+
+    /// Return the footer records that were set aside at the end of the stream,
+    /// in stream order.
+    ///
+    /// See [`ZeroCopyReaderBuilder::skip_footer`] for more detail.
+    #[inline]
+    pub fn footer(&self) -> &[ByteRecord] {
+        &self.footer
+    }
+
     /// Returns whether this reader has been configured to interpret the first
     /// record as a header.
     #[inline]
@@ -221,44 +534,64 @@ impl<R: Read> ZeroCopyReader<R> {
         self.has_headers
     }
 
-    fn read_byte_record_impl(&mut self) -> error::Result<Option<ZeroCopyByteRecord<'_>>> {
-        use ReadResult::*;
+    /// Returns whether the end of the stream was reached in the middle of a
+    /// quoted field, meaning the last record was most likely truncated.
+    ///
+    /// A strict reader returns an
+    /// [`ErrorKind::UnterminatedQuote`](crate::ErrorKind::UnterminatedQuote)
+    /// error in this case, while a non-strict one yields the record as is, so
+    /// this can be checked once the reader is exhausted.
+    #[inline]
+    pub fn ended_in_quote(&self) -> bool {
+        self.inner.ended_in_quote()
+    }
 
+    fn read_byte_record_impl(&mut self) -> error::Result<Option<ZeroCopyByteRecord<'_>>> {
         self.buffer.reset();
         self.seps.clear();
 
-        let byte = self.position();
+        self.tracker.start(self.buffer.position());
+
+        if self
+            .data_end
+            .is_some_and(|end| self.buffer.position() >= end)
+        {
+            return Ok(None);
+        }
+
+        let expected_len = (!self.flexible && self.has_read).then(|| self.raw_headers.0.len() + 1);
 
         loop {
             let seps_offset = self.buffer.saved().len();
             let input = self.buffer.fill_buf()?;
 
-            let (result, pos) =
+            let read =
                 self.inner
                     .split_record_and_find_separators(input, seps_offset, &mut self.seps);
 
-            match result {
-                End => {
-                    self.buffer.consume(pos);
-                    return Ok(None);
-                }
-                Cr | Lf => {
-                    self.buffer.consume(pos);
-                }
-                InputEmpty => {
-                    self.buffer.save();
-                }
-                Record => {
-                    self.index += 1;
-                    self.check_field_count(byte, self.seps.len() + 1)?;
-
-                    let bytes = self.buffer.flush(pos);
-
-                    let record = ZeroCopyByteRecord::new(bytes, &self.seps, self.inner.quote);
+            match self.tracker.zero_copy_step(
+                &mut self.buffer,
+                read,
+                &mut self.inner,
+                &mut self.seps,
+                expected_len,
+            )? {
+                Step::End => return Ok(None),
+                Step::Continue => continue,
+                Step::Record => {
+                    let record = ZeroCopyByteRecord::new(
+                        self.inner.trim_terminator(self.buffer.flushed()),
+                        &self.seps,
+                        self.inner.delimiter,
+                        self.inner.terminator(),
+                        self.inner.quoting(),
+                        self.inner.trim,
+                    )
+                    .with_position(self.tracker.record_position());
 
                     return Ok(Some(record));
                 }
-            };
+            }
         }
     }
 
@@ -268,32 +601,83 @@ impl<R: Read> ZeroCopyReader<R> {
 
         if self.must_reemit_headers {
             self.must_reemit_headers = false;
-            return Ok(Some(ZeroCopyByteRecord::new(
-                &self.raw_headers.1,
-                &self.raw_headers.0,
-                self.inner.quote,
-            )));
+            return Ok(Some(
+                ZeroCopyByteRecord::new(
+                    &self.raw_headers.1,
+                    &self.raw_headers.0,
+                    self.inner.delimiter,
+                    self.inner.terminator(),
+                    self.inner.quoting(),
+                    self.inner.trim,
+                )
+                .with_position(self.headers_position),
+            ));
         }
 
         self.read_byte_record_impl()
     }
 
+    /// Attempt to take a [`Checkpoint`] recording the position of the next
+    /// record to read, so that reading can later be resumed using
+    /// [`ZeroCopyReaderBuilder::resume_from_reader`].
+    pub fn checkpoint(&mut self) -> error::Result<Checkpoint> {
+        self.on_first_read()?;
+
+        let position = if self.must_reemit_headers {
+            self.headers_position
+        } else {
+            self.tracker.position_at(self.buffer.position())
+        };
+
+        Ok(Checkpoint::new(
+            position,
+            self.byte_headers.clone(),
+            self.has_headers,
+            self.inner.dialect(),
+        ))
+    }
+
     /// Returns the current byte offset of the reader in the wrapped stream.
     #[inline(always)]
     pub fn position(&self) -> u64 {
+        // NOTE: the first record, read as headers, is yet to be emitted
         if self.must_reemit_headers {
-            0
+            self.tracker.record_position().byte
         } else {
             self.buffer.position()
         }
     }
 }
 
+impl<R: Read + Seek> ZeroCopyReader<R> {
+    /// Seek the underlying stream to the given [`Position`], typically taken
+    /// from a previously read record, so that the next record read will be
+    /// the one starting there.
+    ///
+    /// Buffered data is discarded, but already read headers are kept and
+    /// records will continue to be numbered from `pos.record`.
+    ///
+    /// **BEWARE**: `pos.byte` is relative to the beginning of the stream as
+    /// reported by [`Self::position`], and must be the exact beginning of a
+    /// CSV record, or yielded records will therefore be incorrect.
+    pub fn seek(&mut self, pos: Position) -> error::Result<()> {
+        self.on_first_read()?;
+
+        self.buffer.seek(pos.byte)?;
+        self.inner.reset();
+        self.must_reemit_headers = false;
+        self.tracker.seek(pos);
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
     use super::*;
+    use crate::error::ErrorKind;
 
     impl<R: Read> ZeroCopyReader<R> {
         fn from_reader_no_headers(reader: R) -> Self {
@@ -369,6 +753,475 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_comment() -> error::Result<()> {
+        let data = "# metadata\nname,surname\n# john,landis\nlucy,rose\n#\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(records, vec![brec!["lucy", "rose"]]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict() -> error::Result<()> {
+        let tests = [
+            ("name,surname\njoh\"n,landis\nlucy,rose", (16, 1)),
+            ("name,surname\n\"john\"ny,landis\nlucy,rose", (19, 1)),
+        ];
+
+        for (data, expected_pos) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .from_reader(Cursor::new(data));
+
+                let err = loop {
+                    match reader.read_byte_record() {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => panic!("should have failed"),
+                        Err(err) => break err,
+                    }
+                };
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == expected_pos
+                ));
+            }
+        }
+
+        // Reading must resume with the record following the malformed one
+        let tests = [
+            ("a,b\nc,d\"e\nf,g\n", (7, 1), brec!["f", "g"]),
+            ("a,b\n\"c\nd\"e,f\ng,h\n", (9, 1), brec!["g", "h"]),
+        ];
+
+        for (data, expected_pos, expected_record) in tests {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                    .strict(true)
+                    .has_headers(false)
+                    .from_reader(Cursor::new(data));
+
+                assert!(reader.read_byte_record()?.is_some());
+
+                let err = reader.read_byte_record().unwrap_err();
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::MalformedQuote { pos: Some(pos) } if *pos == expected_pos
+                ));
+
+                let record = reader.read_byte_record()?.unwrap();
+
+                assert_eq!(record.to_byte_record(), expected_record);
+                assert_eq!(record.position().map(|pos| pos.record), Some(2));
+                assert!(reader.read_byte_record()?.is_none());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unequal_lengths() -> error::Result<()> {
+        let data = "name,surname\njohn,landis\nlucy\n";
+
+        for has_headers in [true, false] {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                    .has_headers(has_headers)
+                    .from_reader(Cursor::new(data));
+
+                let err = loop {
+                    match reader.read_byte_record() {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => panic!("expected an error"),
+                        Err(err) => break err,
+                    }
+                };
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::UnequalLengths {
+                        expected_len: 2,
+                        len: 1,
+                        pos: Some((25, 2))
+                    }
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unterminated_quote() -> error::Result<()> {
+        let data = "name,surname\njohn,\"lan\\";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .strict(true)
+                .escape(Some(b'\\'))
+                .from_reader(Cursor::new(data));
+
+            let err = reader.read_byte_record().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::UnterminatedQuote { pos: Some((13, 1)) }
+            ));
+
+            assert!(reader.read_byte_record()?.is_none());
+            assert!(reader.ended_in_quote());
+
+            // Default should remain lenient, but still remember it
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .escape(Some(b'\\'))
+                .from_reader(Cursor::new(data));
+
+            assert!(reader.read_byte_record()?.is_some());
+            assert!(reader.read_byte_record()?.is_none());
+            assert!(reader.ended_in_quote());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() -> error::Result<()> {
+        let data = format!("name,surname\n\"john,landis\n{}", "lucy,rose\n".repeat(32));
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .max_record_size(64)
+                .from_reader(Cursor::new(&data));
+
+            let err = reader.read_byte_record().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::RecordTooLarge {
+                    pos: Some((13, 1)),
+                    limit: 64
+                }
+            ));
+
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .max_fields(1)
+                .from_reader(Cursor::new(&data));
+
+            let err = reader.byte_headers().unwrap_err();
+
+            assert!(matches!(
+                err.kind(),
+                ErrorKind::TooManyFields {
+                    pos: Some((0, 0)),
+                    limit: 1
+                }
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits_after_skipped_lines() -> error::Result<()> {
+        let comment = format!("#{}\n", "x".repeat(63));
+        let data = format!(
+            "{comment}\n{comment}name,surname\njohn,landis\n{comment}lucy,\"rose\n{}",
+            "a,b\n".repeat(20)
+        );
+
+        for has_headers in [true, false] {
+            for capacity in [32usize, 4, 3, 2, 1] {
+                let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                    .comment(Some(b'#'))
+                    .has_headers(has_headers)
+                    .max_record_size(32)
+                    .from_reader(Cursor::new(&data));
+
+                let err = loop {
+                    match reader.read_byte_record() {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => panic!("expected an error"),
+                        Err(err) => break err,
+                    }
+                };
+
+                assert!(matches!(
+                    err.kind(),
+                    ErrorKind::RecordTooLarge {
+                        pos: Some((221, 2)),
+                        limit: 32
+                    }
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trim() -> error::Result<()> {
+        let data = " name , \" sur name \"\t\njohn,  \"la\"\"ndis\"  \n lucy ,rose \n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .trim(Trim::All)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", " sur name "]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![brec!["john", "la\"ndis"], brec!["lucy", "rose"]]
+            );
+
+            // Trimming the first record as a regular one
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .trim(Trim::Fields)
+                .has_headers(false)
+                .from_reader(Cursor::new(data));
+
+            let record = reader.read_byte_record()?.unwrap();
+            assert_eq!(record.get(0), Some(b"name".as_slice()));
+            assert_eq!(record.get(1), Some(b"\" sur name \"".as_slice()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoting() -> error::Result<()> {
+        let data = "name\tquote\njohn\t\"hello\n\"lucy\"\tok\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .delimiter(b'\t')
+                .quoting(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "quote"]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                assert_eq!(record.unquote(0), record.get(0));
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![brec!["john", "\"hello"], brec!["\"lucy\"", "ok\""]]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_quotes() -> error::Result<()> {
+        fn csv_crate_records(data: &str) -> Vec<ByteRecord> {
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(data.as_bytes())
+                .byte_records()
+                .map(|record| {
+                    let mut converted = ByteRecord::new();
+
+                    for cell in record.unwrap().iter() {
+                        converted.push_field(cell);
+                    }
+
+                    converted
+                })
+                .collect()
+        }
+
+        let data = "name,surname\njoh\"n,landis\nbéatrice,babka\n\"quoted, ok\",5\"\nfoo,\"bar\"\"\"\n5'11\",x\"y\n\"ab\"cd,\"x\"y\"z\"\n\"a\",\n";
+        let expected = csv_crate_records(data);
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(records, expected);
+        }
+
+        // Only a full multi-byte delimiter can precede a field start
+        let data = "name||notes||extra\njohn||a|\"b||c\"d\n\"lucy\"||\"x||y\"||z\n";
+        let expected = vec![
+            brec!["name", "notes", "extra"],
+            brec!["john", "a|\"b", "c\"d"],
+            brec!["lucy", "x||y", "z"],
+        ];
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .delimiter_bytes(b"||")
+                .lenient_quotes(true)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(records, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_empty_lines() -> error::Result<()> {
+        let data = "name\njohn\n\nlucy\r\n\r\n\"\"\n\nbeatrice";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .skip_empty_lines(false)
+                .from_reader(Cursor::new(data));
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    brec!["john"],
+                    brec![""],
+                    brec!["lucy"],
+                    brec![""],
+                    brec![""],
+                    brec![""],
+                    brec!["beatrice"],
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_terminator() -> error::Result<()> {
+        let data = "name,surname\rjohn,\"lan\rdis\"\r\rlucy,rose\nwhatever\r";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .terminator(Terminator::Any(b'\r'))
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![brec!["john", "lan\rdis"], brec!["lucy", "rose\nwhatever"]]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_byte_delimiter() -> error::Result<()> {
+        let data = "name::surname\njohn::\"la::ndis\"\nlucy:::rose\n\"a:\"::b:\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .delimiter_bytes(b"::")
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    brec!["john", "la::ndis"],
+                    brec!["lucy", ":rose"],
+                    brec!["a:", "b:"]
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape() -> error::Result<()> {
+        let data = "name,quote\njohn,\"he said \\\"hello\\\", then \\\\ left\"\n\"lucy \\\"\",\"\\\"\n\"\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .escape(Some(b'\\'))
+                .double_quote(false)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "quote"]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(
+                records,
+                vec![
+                    brec!["john", "he said \"hello\", then \\ left"],
+                    brec!["lucy \"", "\"\n"]
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_byte_headers() -> error::Result<()> {
         let data = b"name,surname\njohn,dandy";
@@ -417,4 +1270,154 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_skip_lines() -> error::Result<()> {
+        let data = "# exported on 2024-01-01\n# by \"someone\n\nname,surname\njohn,landis\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .skip_lines_while(|line| line.is_empty() || line.starts_with(b"#"))
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(
+                reader.preamble()?,
+                b"# exported on 2024-01-01\n# by \"someone\n\n"
+            );
+            assert_eq!(reader.byte_headers()?, &brec!["name", "surname"]);
+            assert_eq!(
+                reader.read_byte_record()?.unwrap().to_byte_record(),
+                brec!["john", "landis"]
+            );
+            assert!(reader.read_byte_record()?.is_none());
+
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .skip_lines(3)
+                .from_reader(Cursor::new(data));
+
+            assert_eq!(reader.preamble()?.len(), 40);
+            assert_eq!(reader.position(), 40);
+            assert_eq!(
+                reader.read_byte_record()?.unwrap().to_byte_record(),
+                brec!["name", "surname"]
+            );
+            assert_eq!(reader.position(), 53);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_footer() -> error::Result<()> {
+        let data = "name,count\r\njohn,1\r\nlucy,2\r\nTOTAL,2,ok\r\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .skip_footer(1)
+                .from_seekable_reader(Cursor::new(data))?;
+
+            assert_eq!(reader.footer(), &[brec!["TOTAL", "2", "ok"]]);
+
+            let mut records = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                records.push(record.to_byte_record());
+            }
+
+            assert_eq!(records, vec![brec!["john", "1"], brec!["lucy", "2"]]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_position() -> error::Result<()> {
+        let data = "sep=,\nname,notes\n\"john\",\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\nc\"\nlast,one";
+
+        let pos = |byte, line, record| Position { byte, line, record };
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .skip_lines(1)
+                .comment(Some(b'#'))
+                .from_reader(data.as_bytes());
+
+            assert_eq!(reader.byte_headers()?.position(), Some(&pos(6, 2, 0)));
+
+            let mut positions = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                positions.push(*record.position().unwrap());
+            }
+
+            assert_eq!(
+                positions,
+                vec![pos(17, 3, 1), pos(48, 7, 2), pos(59, 8, 3), pos(71, 11, 4)]
+            );
+
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .from_reader("john\n\"lucy\nrose\"\nbob".as_bytes());
+
+            let mut positions = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                positions.push(*record.position().unwrap());
+            }
+
+            assert_eq!(positions, vec![pos(0, 1, 0), pos(5, 2, 1), pos(17, 4, 2)]);
+
+            // Lines are counted using the configured terminator
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .has_headers(false)
+                .skip_lines(1)
+                .terminator(Terminator::Any(b';'))
+                .from_reader("meta;a;\"b;c\";d\ne;f".as_bytes());
+
+            let mut positions = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                positions.push(*record.position().unwrap());
+            }
+
+            assert_eq!(
+                positions,
+                vec![pos(5, 2, 0), pos(7, 3, 1), pos(13, 5, 2), pos(17, 6, 3)]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> error::Result<()> {
+        let data =
+            "name,notes\njohn,\"multi\nline\"\n\n# comment\nlucy,rose\r\nbob,\"a\nb\"\nlast,one\n";
+
+        for capacity in [32usize, 4, 3, 2, 1] {
+            let mut reader = ZeroCopyReaderBuilder::with_capacity(capacity)
+                .comment(Some(b'#'))
+                .from_reader(Cursor::new(data));
+
+            let mut expected = Vec::new();
+
+            while let Some(record) = reader.read_byte_record()? {
+                expected.push((record.to_byte_record(), *record.position().unwrap()));
+            }
+
+            for (record, position) in expected.iter().rev() {
+                reader.seek(*position)?;
+
+                let next = reader.read_byte_record()?.unwrap();
+
+                assert_eq!(&next.to_byte_record(), record);
+                assert_eq!(next.position(), Some(position));
+            }
+
+            assert_eq!(reader.byte_headers()?, &brec!["name", "notes"]);
+        }
+
+        Ok(())
+    }
 }